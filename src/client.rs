@@ -1,11 +1,14 @@
+use std::fs::OpenOptions;
 use std::net::TcpStream;
 use std::io::{self, BufWriter, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use log::{info, warn, debug, error};
 use quick_xml::events::{Event as XmlEvent, BytesStart};
 use quick_xml::{Reader, Writer};
-use crate::game::{State, Team, Move};
-use crate::protocol::{Request, Event, GameResult, EventPayload, RequestPayload};
-use crate::util::{SCResult, Element, SCError};
+use crate::game::{GameRecord, State, Team, Move};
+use crate::protocol::{Request, Event, GameResult, EventPayload, RequestPayload, ScoreCause};
+use crate::util::{SCResult, Element, SCError, TimeConfig, TimeManager};
 
 /// A handler that implements the game player's
 /// behavior, usually employing some custom move
@@ -21,9 +24,45 @@ pub trait SCClientDelegate {
     /// with the player's team.
     fn on_welcome(&mut self, _team: Team) {}
     
+    /// Invoked when the opponent is about to think about its
+    /// move if pondering is enabled. The delegate may use the
+    /// time to search the given state in the background.
+    fn start_pondering(&mut self, _state: &State) {}
+
+    /// Invoked when pondering ends, e.g. because the opponent
+    /// has moved. Any background work has to stop before returning.
+    fn stop_pondering(&mut self) {}
+    
     /// Requests a move from the delegate. This method
-    /// should implement the "main" game logic.
-    fn request_move(&mut self, state: &State, my_team: Team) -> Move;
+    /// should implement the "main" game logic and return
+    /// before the time manager's deadline.
+    fn request_move(&mut self, state: &State, my_team: Team, time: &TimeManager) -> Move;
+}
+
+impl<D> SCClientDelegate for Box<D> where D: SCClientDelegate + ?Sized {
+    fn on_update_state(&mut self, state: &State) {
+        (**self).on_update_state(state)
+    }
+
+    fn on_game_end(&mut self, result: &GameResult) {
+        (**self).on_game_end(result)
+    }
+
+    fn on_welcome(&mut self, team: Team) {
+        (**self).on_welcome(team)
+    }
+
+    fn start_pondering(&mut self, state: &State) {
+        (**self).start_pondering(state)
+    }
+
+    fn stop_pondering(&mut self) {
+        (**self).stop_pondering()
+    }
+
+    fn request_move(&mut self, state: &State, my_team: Team, time: &TimeManager) -> Move {
+        (**self).request_move(state, my_team, time)
+    }
 }
 
 /// A configuration that determines whether
@@ -40,13 +79,35 @@ pub struct SCClient<D> where D: SCClientDelegate {
     delegate: D,
     debug_mode: DebugMode,
     reservation_code: Option<String>,
-    // TODO: Add game state
+    time_config: TimeConfig,
+    ponder: bool,
+    record_path: Option<PathBuf>,
 }
 
 impl<D> SCClient<D> where D: SCClientDelegate {
     /// Creates a new client using the specified delegate.
     pub fn new(delegate: D, debug_mode: DebugMode, reservation_code: Option<String>) -> Self {
-        Self { delegate, debug_mode, reservation_code }
+        Self { delegate, debug_mode, reservation_code, time_config: TimeConfig::default(), ponder: false, record_path: None }
+    }
+
+    /// Sets the time available to the delegate per move.
+    pub fn time_config(mut self, time_config: TimeConfig) -> Self {
+        self.time_config = time_config;
+        self
+    }
+
+    /// Sets whether the delegate should ponder
+    /// during the opponent's turn.
+    pub fn ponder(mut self, ponder: bool) -> Self {
+        self.ponder = ponder;
+        self
+    }
+
+    /// Sets a file to which the played game is
+    /// appended as a `GameRecord` line.
+    pub fn record_path(mut self, record_path: Option<PathBuf>) -> Self {
+        self.record_path = record_path;
+        self
     }
     
     /// Blocks the thread and begins reading XML messages
@@ -109,8 +170,12 @@ impl<D> SCClient<D> where D: SCClientDelegate {
         // Handle events from the server
         let mut state: Option<State> = None;
         let mut game_result: Option<GameResult> = None;
+        let mut my_team: Option<Team> = None;
+        let mut pondering = false;
+        let mut record: Option<GameRecord> = None;
         loop {
             let event_xml = Element::read_from(&mut reader)?;
+            let received = Instant::now();
 
             debug!("Got event {}", event_xml);
             match Event::try_from(&event_xml) {
@@ -119,27 +184,68 @@ impl<D> SCClient<D> where D: SCClientDelegate {
                 },
                 Ok(Event::Left { room_id }) => {
                     info!("Left room {}", room_id);
+                    if pondering {
+                        self.delegate.stop_pondering();
+                    }
                     break;
                 },
                 Ok(Event::Room { room_id, payload }) => {
                     info!("Got {} in room {}", payload, room_id);
+                    if pondering && !matches!(payload, EventPayload::Welcome(_)) {
+                        self.delegate.stop_pondering();
+                        pondering = false;
+                    }
                     match payload {
-                        EventPayload::Welcome(team) => self.delegate.on_welcome(team),
+                        EventPayload::Welcome(team) => {
+                            my_team = Some(team);
+                            self.delegate.on_welcome(team);
+                        },
                         EventPayload::GameResult(result) => {
+                            let cause = result.scores().iter()
+                                .find(|(player, _)| Some(player.team()) == my_team)
+                                .map(|(_, score)| score.cause());
+                            if let Some(cause @ (ScoreCause::SoftTimeout | ScoreCause::HardTimeout)) = cause {
+                                error!("Lost the game due to {:?}, consider increasing the safety margin", cause);
+                            }
+                            if let (Some(path), Some(record)) = (&self.record_path, &record) {
+                                if let Err(e) = Self::append_record(path, record) {
+                                    warn!("Could not record game to {}: {:?}", path.display(), e);
+                                }
+                            }
                             self.delegate.on_game_end(&result);
                             game_result = Some(result);
                         },
                         EventPayload::Memento(new_state) => {
+                            debug!("Got state {}\n{:#}", new_state, new_state);
                             self.delegate.on_update_state(&new_state);
-                            state = Some(new_state);
+                            match (&mut record, new_state.last_move()) {
+                                (Some(record), Some(m)) => record.push(m),
+                                (Some(_), None) => {},
+                                (None, _) => record = Some(GameRecord::new((*new_state).clone())),
+                            }
+                            let opponent_to_move = my_team.is_some_and(|t| new_state.current_team() == Some(t.opponent()));
+                            if self.ponder && opponent_to_move && !new_state.is_over() {
+                                self.delegate.start_pondering(&new_state);
+                                pondering = true;
+                            }
+                            state = Some(*new_state);
                         },
                         EventPayload::MoveRequest => {
                             let state = state.as_ref().ok_or_else(|| SCError::InvalidState("No state available at move request!".to_owned()))?;
                             let team = state.current_team().ok_or_else(|| SCError::InvalidState("No team available at move request!".to_owned()))?;
-                            let new_move = self.delegate.request_move(state, team);
+                            let time = TimeManager::new(received, &self.time_config);
+                            let mut new_move = self.delegate.request_move(state, team, &time);
+                            if let Err(e) = state.validate_move(new_move) {
+                                error!("Delegate chose invalid move {}: {}", new_move, e);
+                                new_move = *state.possible_moves().first().ok_or(SCError::InvalidMove(e))?;
+                                warn!("Falling back to move {}", new_move);
+                            }
                             let request = Request::Room { room_id, payload: RequestPayload::Move(new_move) };
                             let request_xml = Element::from(request);
                             request_xml.write_to(&mut writer)?;
+                            if time.elapsed() > self.time_config.usable() {
+                                warn!("Sent move after {} ms, eating into the safety margin", time.elapsed().as_millis());
+                            }
                         },
                     };
                 },
@@ -161,4 +267,11 @@ impl<D> SCClient<D> where D: SCClientDelegate {
             Err(SCError::InvalidState("Failed to receive game_result".to_string()))
         }
     }
+
+    /// Appends the given game to the record file.
+    fn append_record(path: &Path, record: &GameRecord) -> SCResult<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", record)?;
+        Ok(())
+    }
 }
@@ -0,0 +1,36 @@
+use crate::game::{State, Team};
+
+/// A heuristic that scores a state from the perspective of a team.
+/// Higher scores are better for the team.
+pub trait Evaluator {
+    /// Scores the given state from the given team's perspective.
+    fn evaluate(&self, state: &State, team: Team) -> i32;
+}
+
+impl<F> Evaluator for F where F: Fn(&State, Team) -> i32 {
+    fn evaluate(&self, state: &State, team: Team) -> i32 {
+        self(state, team)
+    }
+}
+
+/// Evaluators can be combined by summing them as a pair.
+impl<A, B> Evaluator for (A, B) where A: Evaluator, B: Evaluator {
+    fn evaluate(&self, state: &State, team: Team) -> i32 {
+        self.0.evaluate(state, team) + self.1.evaluate(state, team)
+    }
+}
+
+/// A simple evaluator that counts ambers and the
+/// number of pieces (including those in towers).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimpleEvaluator;
+
+impl Evaluator for SimpleEvaluator {
+    fn evaluate(&self, state: &State, team: Team) -> i32 {
+        let ambers = state.ambers_of(team) as i32 - state.ambers_of(team.opponent()) as i32;
+        let pieces: i32 = state.board().iter_pieces()
+            .map(|(_, piece)| if piece.team() == team { piece.count() as i32 } else { -(piece.count() as i32) })
+            .sum();
+        100 * ambers + 10 * pieces
+    }
+}
@@ -0,0 +1,9 @@
+mod evaluator;
+mod term;
+mod weighted;
+mod tuning;
+
+pub use evaluator::*;
+pub use term::*;
+pub use weighted::*;
+pub use tuning::*;
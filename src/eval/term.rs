@@ -0,0 +1,195 @@
+use std::{fmt, str::FromStr};
+
+use crate::{game::{Board, PieceType, State, Team}, util::{SCError, SCResult}};
+
+use super::Evaluator;
+
+/// A basic evaluation term. Each term measures a feature of the
+/// position as the difference between the team and its opponent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Term {
+    /// The number of ambers.
+    Ambers,
+    /// The number of pieces (or towers) with the given type on top.
+    Material(PieceType),
+    /// The number of pieces stacked onto other pieces.
+    Towers,
+    /// The summed distances of light pieces from the own start line,
+    /// i.e. their advancement towards `Board::start_line(opponent)`.
+    Advancement,
+    /// The number of possible moves.
+    Mobility,
+    /// The number of opponent pieces that can be captured.
+    Threats,
+}
+
+impl Term {
+    /// The number of terms.
+    pub const COUNT: usize = 9;
+
+    /// All terms.
+    pub const ALL: [Term; Self::COUNT] = [
+        Self::Ambers,
+        Self::Material(PieceType::Herzmuschel),
+        Self::Material(PieceType::Moewe),
+        Self::Material(PieceType::Seestern),
+        Self::Material(PieceType::Robbe),
+        Self::Towers,
+        Self::Advancement,
+        Self::Mobility,
+        Self::Threats,
+    ];
+
+    /// The term's index in `Term::ALL`.
+    pub fn index(self) -> usize {
+        match self {
+            Self::Ambers => 0,
+            Self::Material(piece_type) => 1 + piece_type.index(),
+            Self::Towers => 5,
+            Self::Advancement => 6,
+            Self::Mobility => 7,
+            Self::Threats => 8,
+        }
+    }
+
+    /// The term's name as used in weight files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ambers => "ambers",
+            Self::Material(PieceType::Herzmuschel) => "material_herzmuschel",
+            Self::Material(PieceType::Moewe) => "material_moewe",
+            Self::Material(PieceType::Seestern) => "material_seestern",
+            Self::Material(PieceType::Robbe) => "material_robbe",
+            Self::Towers => "towers",
+            Self::Advancement => "advancement",
+            Self::Mobility => "mobility",
+            Self::Threats => "threats",
+        }
+    }
+
+    /// Measures the term for the given team.
+    pub fn evaluate(self, state: &State, team: Team) -> i32 {
+        let board = state.board();
+        let opponent = team.opponent();
+        match self {
+            Self::Ambers => state.ambers_of(team) as i32 - state.ambers_of(opponent) as i32,
+            Self::Material(piece_type) => board.iter_pieces()
+                .filter(|(_, piece)| piece.piece_type() == piece_type)
+                .map(|(_, piece)| if piece.team() == team { 1 } else { -1 })
+                .sum(),
+            Self::Towers => board.iter_pieces()
+                .map(|(_, piece)| {
+                    let stacked = piece.count() as i32 - 1;
+                    if piece.team() == team { stacked } else { -stacked }
+                })
+                .sum(),
+            Self::Advancement => board.iter_pieces()
+                .filter(|(_, piece)| piece.piece_type().is_light())
+                .map(|(pos, piece)| {
+                    let distance = (pos.x - Board::start_line(piece.team())).abs();
+                    if piece.team() == team { distance } else { -distance }
+                })
+                .sum(),
+            Self::Mobility | Self::Threats => self.evaluate_activity(&Activity::of(board), team),
+        }
+    }
+
+    /// Measures `Mobility` or `Threats` from the given activity, which
+    /// lets an evaluator share one pass over the board between both.
+    pub(super) fn evaluate_activity(self, activity: &Activity, team: Team) -> i32 {
+        let (own, other) = (activity.of_team(team), activity.of_team(team.opponent()));
+        match self {
+            Self::Mobility => own.0 as i32 - other.0 as i32,
+            Self::Threats => own.1.count_ones() as i32 - other.1.count_ones() as i32,
+            _ => panic!("{} does not depend on the activity!", self),
+        }
+    }
+
+    /// Whether the term is measured from the `Activity`.
+    pub(super) fn is_activity(self) -> bool {
+        matches!(self, Self::Mobility | Self::Threats)
+    }
+}
+
+/// The number of moves and the bitset of capturable fields of each
+/// team, gathered in a single pass without generating moves.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Activity {
+    teams: [(usize, u64); 2],
+}
+
+impl Activity {
+    /// Gathers the activity on the given board.
+    pub(super) fn of(board: &Board) -> Self {
+        let mut activity = Self::default();
+        for (pos, piece) in board.iter_pieces() {
+            let (moves, attacked) = &mut activity.teams[piece.team().index() as usize];
+            for dest in piece.possible_directions().map(|delta| pos + delta).filter(|&dest| board.can_move(piece, dest)) {
+                *moves += 1;
+                if board.get(dest).is_some() {
+                    *attacked |= 1 << Board::field_index(dest);
+                }
+            }
+        }
+        activity
+    }
+
+    /// The number of moves and the capturable fields of the team.
+    fn of_team(&self, team: Team) -> (usize, u64) {
+        self.teams[team.index() as usize]
+    }
+}
+
+impl Evaluator for Term {
+    fn evaluate(&self, state: &State, team: Team) -> i32 {
+        Term::evaluate(*self, state, team)
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Term {
+    type Err = SCError;
+
+    fn from_str(s: &str) -> SCResult<Self> {
+        Self::ALL.into_iter()
+            .find(|t| t.name() == s)
+            .ok_or_else(|| SCError::UnknownVariant(format!("Unknown evaluation term {}", s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::game::{PieceType, State, Team};
+
+    use super::Term;
+
+    #[test]
+    fn test_terms() {
+        let state = State::from_str("8/1R[2]6/3m4/8/8/8/8/M5h1 1:0 0 1 -").unwrap();
+        let measure = |term: Term| term.evaluate(&state, Team::One);
+        assert_eq!(measure(Term::Ambers), 1);
+        assert_eq!(measure(Term::Material(PieceType::Robbe)), 1);
+        assert_eq!(measure(Term::Material(PieceType::Moewe)), 0);
+        assert_eq!(measure(Term::Material(PieceType::Herzmuschel)), -1);
+        assert_eq!(measure(Term::Towers), 1);
+        assert_eq!(measure(Term::Advancement), -(4 + 1));
+        assert_eq!(measure(Term::Mobility), (4 + 2) - (4 + 1));
+        assert_eq!(measure(Term::Threats), 1);
+        assert_eq!(Term::Threats.evaluate(&state, Team::Two), -1);
+    }
+
+    #[test]
+    fn test_names() {
+        for (i, term) in Term::ALL.into_iter().enumerate() {
+            assert_eq!(term.index(), i);
+            assert_eq!(Term::from_str(term.name()).unwrap(), term);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use log::debug;
+
+use crate::game::{GameRecord, State, Team};
+
+use super::{Term, Weights};
+
+/// The default score at which the win probability is about 73%,
+/// i.e. the scale of the sigmoid mapping scores to results.
+pub const DEFAULT_TUNING_SCALE: f64 = 400.0;
+/// The largest step by which a weight is changed at once.
+const INITIAL_STEP: i32 = 64;
+
+/// A position along with the game's result, as used for tuning.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TuningSample {
+    /// The terms from the first team's perspective.
+    features: [i32; Term::COUNT],
+    /// The result from the first team's perspective, i.e.
+    /// 1 for a win, 0.5 for a draw and 0 for a loss.
+    result: f64,
+}
+
+impl TuningSample {
+    /// Creates a sample from a state of a game won by the given team.
+    pub fn new(state: &State, winner: Option<Team>) -> Self {
+        let mut features = [0; Term::COUNT];
+        for term in Term::ALL {
+            features[term.index()] = term.evaluate(state, Team::One);
+        }
+        let result = match winner {
+            Some(Team::One) => 1.0,
+            Some(Team::Two) => 0.0,
+            None => 0.5,
+        };
+        Self { features, result }
+    }
+
+    /// Collects samples from the quiet positions of a finished game,
+    /// i.e. those without tactical moves, whose static evaluation is
+    /// meaningful.
+    pub fn from_record(record: &GameRecord) -> Vec<Self> {
+        let Some(outcome) = record.outcome() else { return Vec::new() };
+        record.positions()
+            .filter(|(state, _)| state.capture_moves().next().is_none())
+            .map(|(state, _)| Self::new(&state, outcome.winner()))
+            .collect()
+    }
+
+    /// The result from the first team's perspective.
+    #[inline]
+    pub fn result(&self) -> f64 { self.result }
+
+    /// The evaluation with the given weights from the first team's perspective.
+    pub fn score(&self, weights: &Weights) -> i64 {
+        weights.iter()
+            .map(|(term, weight)| weight as i64 * self.features[term.index()] as i64)
+            .sum()
+    }
+}
+
+/// Fits evaluation weights to game results (Texel's tuning method):
+/// scores are mapped to expected results by a sigmoid and the mean
+/// squared error against the actual results is minimized by a local
+/// search over the weights.
+pub struct Tuner {
+    samples: Vec<TuningSample>,
+    scale: f64,
+}
+
+impl Tuner {
+    /// Creates a tuner fitting the given samples.
+    pub fn new(samples: Vec<TuningSample>) -> Self {
+        Self { samples, scale: DEFAULT_TUNING_SCALE }
+    }
+
+    /// Sets the scale of the sigmoid.
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// The number of samples.
+    #[inline]
+    pub fn sample_count(&self) -> usize { self.samples.len() }
+
+    /// The expected result for a score, i.e. the win probability.
+    fn expected(&self, score: i64) -> f64 {
+        1.0 / (1.0 + (-(score as f64) / self.scale).exp())
+    }
+
+    /// The mean squared error of the weights' predictions.
+    pub fn error(&self, weights: &Weights) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self.samples.iter()
+            .map(|s| (s.result - self.expected(s.score(weights))).powi(2))
+            .sum();
+        sum / self.samples.len() as f64
+    }
+
+    /// Tunes the weights, starting with the given ones, by repeatedly
+    /// changing single weights as long as the error decreases. The step
+    /// size is halved whenever no change helps. Stops after the given
+    /// number of passes over all terms.
+    pub fn tune(&self, mut weights: Weights, max_passes: usize) -> Weights {
+        let mut best_error = self.error(&weights);
+        let mut step = INITIAL_STEP;
+        for pass in 0..max_passes {
+            let mut improved = false;
+            for term in Term::ALL {
+                for delta in [step, -step] {
+                    let candidate = weights.with(term, weights.get(term) + delta);
+                    let error = self.error(&candidate);
+                    if error < best_error {
+                        weights = candidate;
+                        best_error = error;
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+            debug!("Tuning pass {}: error {:.6} (step {})", pass, best_error, step);
+            if !improved {
+                if step == 1 {
+                    break;
+                }
+                step /= 2;
+            }
+        }
+        weights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{eval::{Term, Tuner, TuningSample, Weights}, game::{GameRecord, State, Team}};
+
+    #[test]
+    fn test_samples() {
+        let record = GameRecord::play(State::initial(2), |state| state.moves().next().unwrap());
+        let winner = record.outcome().unwrap().winner();
+        let samples = TuningSample::from_record(&record);
+        assert!(!samples.is_empty() && samples.len() <= record.moves().len());
+        let expected = match winner {
+            Some(Team::One) => 1.0,
+            Some(Team::Two) => 0.0,
+            None => 0.5,
+        };
+        assert!(samples.iter().all(|s| s.result() == expected));
+    }
+
+    #[test]
+    fn test_tune() {
+        // Games won by the team with more material should
+        // be explained by positive material weights
+        let samples = [
+            ("R6r/H6s/M6m/H6s/S6h/M6m/S6h/R7 0:0 0 1 -", Some(Team::One)),
+            ("R6r/H6s/M6m/H6s/S6h/M6m/S7/R6r 0:0 0 1 -", Some(Team::One)),
+            ("R6r/H6s/M6m/H6s/7h/M6m/S6h/R6r 0:0 0 1 -", Some(Team::Two)),
+            ("7r/H6s/M6m/H6s/S6h/M6m/S6h/R6r 0:0 0 1 -", Some(Team::Two)),
+            ("R6r/H6s/M6m/H6s/S6h/M6m/S6h/R6r 0:0 0 1 -", None),
+        ].into_iter()
+            .map(|(notation, winner)| TuningSample::new(&State::from_str(notation).unwrap(), winner))
+            .collect::<Vec<_>>();
+
+        let tuner = Tuner::new(samples);
+        let initial = Weights::zero();
+        let tuned = tuner.tune(initial, 50);
+        assert!(tuner.error(&tuned) < tuner.error(&initial));
+        assert!(tuned.iter().filter(|(t, _)| matches!(t, Term::Material(_))).any(|(_, w)| w > 0));
+    }
+}
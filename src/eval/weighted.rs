@@ -0,0 +1,149 @@
+use std::{fmt, fs, path::Path, str::FromStr};
+
+use crate::{game::{PieceType, State, Team}, util::{SCError, SCResult}};
+
+use super::{Activity, Evaluator, Term};
+
+/// A weight for each evaluation term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Weights {
+    values: [i32; Term::COUNT],
+}
+
+impl Weights {
+    /// Creates weights that are all zero.
+    pub fn zero() -> Self {
+        Self { values: [0; Term::COUNT] }
+    }
+
+    /// The weight of the given term.
+    #[inline]
+    pub fn get(&self, term: Term) -> i32 { self.values[term.index()] }
+
+    /// Sets the weight of the given term.
+    #[inline]
+    pub fn set(&mut self, term: Term, weight: i32) { self.values[term.index()] = weight; }
+
+    /// Returns the weights with the given term's weight replaced.
+    pub fn with(mut self, term: Term, weight: i32) -> Self {
+        self.set(term, weight);
+        self
+    }
+
+    /// The terms along with their weights.
+    pub fn iter(&self) -> impl Iterator<Item=(Term, i32)> + '_ {
+        Term::ALL.into_iter().map(move |t| (t, self.get(t)))
+    }
+
+    /// Reads weights from the given file.
+    pub fn load(path: impl AsRef<Path>) -> SCResult<Self> {
+        Self::from_str(&fs::read_to_string(path)?)
+    }
+
+    /// Writes the weights to the given file.
+    pub fn save(&self, path: impl AsRef<Path>) -> SCResult<()> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self::zero()
+            .with(Term::Ambers, 1000)
+            .with(Term::Material(PieceType::Herzmuschel), 40)
+            .with(Term::Material(PieceType::Moewe), 50)
+            .with(Term::Material(PieceType::Seestern), 50)
+            .with(Term::Material(PieceType::Robbe), 60)
+            .with(Term::Towers, 80)
+            .with(Term::Advancement, 6)
+            .with(Term::Mobility, 2)
+            .with(Term::Threats, 15)
+    }
+}
+
+/// Formats the weights as `name = weight` lines.
+impl fmt::Display for Weights {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (term, weight) in self.iter() {
+            writeln!(f, "{} = {}", term, weight)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses `name = weight` pairs separated by newlines or commas. Terms
+/// that are not mentioned keep their default weight, lines starting
+/// with `#` are ignored.
+impl FromStr for Weights {
+    type Err = SCError;
+
+    fn from_str(s: &str) -> SCResult<Self> {
+        let mut weights = Self::default();
+        for entry in s.split(['\n', ',']).map(|e| e.trim()).filter(|e| !e.is_empty() && !e.starts_with('#')) {
+            let (name, weight) = entry.split_once('=')
+                .ok_or_else(|| SCError::InvalidNotation(format!("Expected 'name = weight', got '{}'", entry)))?;
+            weights.set(name.trim().parse()?, weight.trim().parse()?);
+        }
+        Ok(weights)
+    }
+}
+
+/// An evaluator computing a weighted sum of the terms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WeightedEvaluator {
+    weights: Weights,
+}
+
+impl WeightedEvaluator {
+    /// Creates a new evaluator with the given weights.
+    pub fn new(weights: Weights) -> Self {
+        Self { weights }
+    }
+
+    /// The weights of the terms.
+    #[inline]
+    pub fn weights(&self) -> &Weights { &self.weights }
+}
+
+impl Evaluator for WeightedEvaluator {
+    fn evaluate(&self, state: &State, team: Team) -> i32 {
+        // Terms with a zero weight are skipped, the others share the activity
+        let mut activity = None;
+        self.weights.iter()
+            .filter(|&(_, weight)| weight != 0)
+            .map(|(term, weight)| weight * if term.is_activity() {
+                term.evaluate_activity(activity.get_or_insert_with(|| Activity::of(state.board())), team)
+            } else {
+                term.evaluate(state, team)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{eval::{Evaluator, Term, Weights, WeightedEvaluator}, game::{PieceType, State, Team}};
+
+    #[test]
+    fn test_parsing() {
+        let weights = Weights::from_str("ambers = 7, towers=3\n# comment\nmaterial_robbe = -2").unwrap();
+        assert_eq!(weights, Weights::default().with(Term::Ambers, 7).with(Term::Towers, 3).with(Term::Material(PieceType::Robbe), -2));
+        assert_eq!(Weights::from_str(&weights.to_string()).unwrap(), weights);
+        assert!(Weights::from_str("foo = 1").is_err());
+        assert!(Weights::from_str("ambers 1").is_err());
+    }
+
+    #[test]
+    fn test_weighted_sum() {
+        let state = State::from_str("8/1R[2]6/3m4/8/8/8/8/M5h1 1:0 0 1 -").unwrap();
+        let evaluator = WeightedEvaluator::new(Weights::zero().with(Term::Ambers, 100).with(Term::Towers, 10));
+        assert_eq!(evaluator.evaluate(&state, Team::One), 110);
+        assert_eq!(evaluator.evaluate(&state, Team::Two), -110);
+        let evaluator = WeightedEvaluator::default();
+        let expected: i32 = Term::ALL.into_iter().map(|t| evaluator.weights().get(t) * t.evaluate(&state, Team::One)).sum();
+        assert_eq!(evaluator.evaluate(&state, Team::One), expected);
+    }
+}
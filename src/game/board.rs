@@ -1,37 +1,105 @@
-use std::{collections::HashMap, ops::Index};
+use std::{collections::HashMap, fmt, ops::Index};
+
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
 
 use crate::util::{Element, SCError, SCResult};
 
-use super::{Vec2, Piece, Move, Team};
+use super::{Vec2, Piece, PieceType, Move, Team, BoardUndo, Render};
 
 pub const BOARD_SIZE: usize = 8;
+pub const FIELD_COUNT: usize = BOARD_SIZE * BOARD_SIZE;
 
 /// An 8x8 game board storing the pieces (8 pieces per team).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Board {
-    /// The pieces on the board keyed by position.
-    pieces: HashMap<Vec2, Piece>,
+    /// The fields of the board, indexed by `Board::field_index`.
+    fields: [Option<Piece>; FIELD_COUNT],
 }
 
 impl Board {
     /// Creates a new empty board.
     pub fn empty() -> Self {
-        Self { pieces: HashMap::new() }
+        Self { fields: [None; FIELD_COUNT] }
     }
 
     /// Creates a new board with the given pieces.
-    pub fn new(pieces: impl Into<HashMap<Vec2, Piece>>) -> Self {
-        Self { pieces: pieces.into() }
+    pub fn new(pieces: impl IntoIterator<Item=(Vec2, Piece)>) -> Self {
+        let mut board = Self::empty();
+        for (pos, piece) in pieces {
+            board.set(pos, Some(piece));
+        }
+        board
+    }
+
+    /// Creates the start setup like the server does: The first team's
+    /// start line holds a randomly shuffled line of two pieces of each
+    /// type, the second team's start line holds the point-mirrored line.
+    pub fn initial(seed: u64) -> Self {
+        let mut line: Vec<PieceType> = PieceType::ALL.iter().flat_map(|&t| [t, t]).collect();
+        line.shuffle(&mut StdRng::seed_from_u64(seed));
+        let last = BOARD_SIZE as i32 - 1;
+        Self::new(line.into_iter().enumerate().flat_map(|(i, piece_type)| [
+            (Vec2::new(Self::start_line(Team::One), i as i32), Piece::new(piece_type, Team::One, 1)),
+            (Vec2::new(Self::start_line(Team::Two), last - i as i32), Piece::new(piece_type, Team::Two, 1)),
+        ]))
+    }
+
+    /// The index of the field at the given position. Panics
+    /// if the position is out of bounds.
+    #[inline]
+    pub fn field_index(pos: Vec2) -> usize {
+        assert!(Board::is_in_bounds(pos), "Position {} wasn't in bounds!", pos);
+        pos.y as usize * BOARD_SIZE + pos.x as usize
+    }
+
+    /// The position of the field with the given index.
+    #[inline]
+    pub fn field_pos(index: usize) -> Vec2 {
+        Vec2::new((index % BOARD_SIZE) as i32, (index / BOARD_SIZE) as i32)
+    }
+
+    /// The pieces on the board keyed by position. Prefer
+    /// `iter_pieces`, which doesn't allocate.
+    pub fn pieces(&self) -> HashMap<Vec2, Piece> {
+        self.iter_pieces().collect()
     }
 
-    /// The pieces on the board.
-    pub fn pieces(&self) -> &HashMap<Vec2, Piece> { &self.pieces }
+    /// The pieces on the board along with their positions.
+    pub fn iter_pieces(&self) -> impl Iterator<Item=(Vec2, Piece)> + '_ {
+        self.fields.iter()
+            .enumerate()
+            .filter_map(|(i, field)| field.map(|piece| (Self::field_pos(i), piece)))
+    }
+
+    /// The number of pieces (or towers) on the board.
+    pub fn piece_count(&self) -> usize {
+        self.fields.iter().filter(|field| field.is_some()).count()
+    }
 
     /// Fetches a piece on the board.
-    pub fn get(&self, pos: Vec2) -> Option<Piece> { self.pieces.get(&pos).cloned() }
+    #[inline]
+    pub fn get(&self, pos: Vec2) -> Option<Piece> {
+        if Board::is_in_bounds(pos) { self.fields[Self::field_index(pos)] } else { None }
+    }
 
     /// Fetches a piece on the board mutably.
-    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut Piece> { self.pieces.get_mut(&pos) }
+    #[inline]
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut Piece> {
+        if Board::is_in_bounds(pos) { self.fields[Self::field_index(pos)].as_mut() } else { None }
+    }
+
+    /// Places a piece at the given position or clears it,
+    /// returning the piece previously there.
+    #[inline]
+    pub fn set(&mut self, pos: Vec2, piece: Option<Piece>) -> Option<Piece> {
+        std::mem::replace(&mut self.fields[Self::field_index(pos)], piece)
+    }
+
+    /// Removes the piece at the given position.
+    #[inline]
+    pub fn remove(&mut self, pos: Vec2) -> Option<Piece> {
+        self.set(pos, None)
+    }
 
     /// Checks whether a position in in-bounds.
     pub fn is_in_bounds(pos: Vec2) -> bool {
@@ -45,28 +113,35 @@ impl Board {
 
     // Partially translated from https://github.com/software-challenge/backend/blob/89407e5e2f76801ec8beb8f31412da218f5f70e5/plugin/src/main/kotlin/sc/plugin2022/Board.kt
 
-    /// Applies a move to the board.
-    pub fn perform(&mut self, m: Move) {
-        if let Some(piece) = self.pieces.remove(&m.from()) {
+    /// Applies a move to the board, returning the information
+    /// needed to revert it.
+    pub fn perform(&mut self, m: Move) -> BoardUndo {
+        if let Some(piece) = self.remove(m.from()) {
             debug_assert!(Board::is_in_bounds(m.to()), "Move destination {} wasn't in bounds!", m.to());
             debug_assert!(piece.possible_directions().any(|v| v == m.delta()), "Move delta {} isn't in the allowed move for the piece {:?}!", m.delta(), piece);
-            let new_piece = self.pieces.get(&m.to()).map(|&p| piece.capture(p)).unwrap_or(piece);
-            self.pieces.insert(m.to(), new_piece);
+            let captured = self.get(m.to());
+            let new_piece = captured.map(|p| piece.capture(p)).unwrap_or(piece);
+            self.set(m.to(), Some(new_piece));
+            BoardUndo { m, piece, captured }
         } else {
             panic!("Cannot perform empty move!");
         }
     }
 
+    /// Reverts a move previously performed on the board, including
+    /// a possible removal of the moved piece by `check_amber`.
+    pub fn unmake(&mut self, undo: BoardUndo) {
+        self.set(undo.m.to(), undo.captured);
+        self.set(undo.m.from(), Some(undo.piece));
+    }
+
     /// Checks whether the piece at the given position should be turned
     /// into an amber and, if so, removes it.
     pub fn check_amber(&mut self, pos: Vec2) -> usize {
-        if let Some(piece) = self.pieces.get_mut(&pos) {
-            let ambers = [
-                piece.is_amber(),
-                piece.piece_type().is_light() && pos.x == Self::start_line(piece.team().opponent()),
-            ].into_iter().map(|b| if b { 1 } else { 0 }).sum();
+        if let Some(piece) = self.get(pos) {
+            let ambers = Self::ambers_at(piece, pos);
             if ambers > 0 {
-                self.pieces.remove(&pos);
+                self.remove(pos);
             }
             ambers
         } else {
@@ -74,10 +149,41 @@ impl Board {
         }
     }
 
+    /// The number of ambers the given piece turns into at the given position.
+    fn ambers_at(piece: Piece, pos: Vec2) -> usize {
+        [
+            piece.is_amber(),
+            piece.piece_type().is_light() && pos.x == Self::start_line(piece.team().opponent()),
+        ].into_iter().map(|b| if b { 1 } else { 0 }).sum()
+    }
+
+    /// The number of ambers the given move would earn, without performing it.
+    pub fn amber_gain(&self, m: Move) -> usize {
+        self.get(m.from()).map_or(0, |piece| {
+            let moved = self.get(m.to()).map_or(piece, |captured| piece.capture(captured));
+            Self::ambers_at(moved, m.to())
+        })
+    }
+
+    /// Checks whether the given move captures a piece.
+    #[inline]
+    pub fn is_capture(&self, m: Move) -> bool {
+        self.get(m.to()).is_some()
+    }
+
     /// Checks whether the given piece can jump to the destination, not
     /// accounting for whether the move itself is valid.
     pub fn can_move(&self, piece: Piece, dest: Vec2) -> bool {
-        Board::is_in_bounds(dest) && self.pieces.get(&dest).map(|p| p.team()) != Some(piece.team())
+        Board::is_in_bounds(dest) && self.get(dest).map(|p| p.team()) != Some(piece.team())
+    }
+
+    /// Lazily generates the moves of the given team's pieces.
+    pub fn moves_of(&self, team: Team) -> impl Iterator<Item=Move> + '_ {
+        self.iter_pieces()
+            .filter(move |(_, piece)| piece.team() == team)
+            .flat_map(move |(pos, piece)| piece.possible_directions()
+                .map(move |delta| Move::new(pos, pos + delta))
+                .filter(move |m| self.can_move(piece, m.to())))
     }
 
     /// Fetches possible move deltas from the given position.
@@ -96,7 +202,13 @@ impl Index<Vec2> for Board {
     type Output = Piece;
 
     fn index(&self, index: Vec2) -> &Piece {
-        &self.pieces[&index]
+        self.fields[Self::field_index(index)].as_ref().unwrap_or_else(|| panic!("No piece at {}!", index))
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Render::new(self))
     }
 }
 
@@ -104,17 +216,18 @@ impl TryFrom<&Element> for Board {
     type Error = SCError;
 
     fn try_from(elem: &Element) -> SCResult<Self> {
-        Ok(Board {
-            pieces: elem
-                .child_by_name("pieces")?
-                .childs_by_name("entry")
-                .map(|e| {
-                    let coords = Vec2::try_from(e.child_by_name("coordinates")?)?;
-                    let piece = Piece::try_from(e.child_by_name("piece")?)?;
-                    Ok((coords, piece))
-                })
-                .collect::<SCResult<_>>()?,
-        })
+        Ok(Board::new(elem
+            .child_by_name("pieces")?
+            .childs_by_name("entry")
+            .map(|e| {
+                let coords = Vec2::try_from(e.child_by_name("coordinates")?)?;
+                if !Board::is_in_bounds(coords) {
+                    return Err(SCError::InvalidState(format!("Piece coordinates {} are out of bounds!", coords)));
+                }
+                let piece = Piece::try_from(e.child_by_name("piece")?)?;
+                Ok((coords, piece))
+            })
+            .collect::<SCResult<Vec<_>>>()?))
     }
 }
 
@@ -122,7 +235,7 @@ impl TryFrom<&Element> for Board {
 mod tests {
     use std::str::FromStr;
 
-    use crate::{util::Element, game::{Piece, PieceType, Team, Board, Vec2}, hashmap};
+    use crate::{util::Element, game::{Piece, PieceType, Team, Board, Vec2, Move}, hashmap};
 
     #[test]
     fn test_parsing() {
@@ -144,4 +257,79 @@ mod tests {
             Vec2::new(1, 0) => Piece::new(PieceType::Robbe, Team::Two, 1)
         ]));
     }
+
+    #[test]
+    fn test_fields() {
+        let board = Board::new(hashmap![
+            Vec2::new(7, 7) => Piece::new(PieceType::Moewe, Team::Two, 2),
+            Vec2::new(3, 0) => Piece::new(PieceType::Seestern, Team::One, 1)
+        ]);
+        assert_eq!(board.iter_pieces().collect::<Vec<_>>(), vec![
+            (Vec2::new(3, 0), Piece::new(PieceType::Seestern, Team::One, 1)),
+            (Vec2::new(7, 7), Piece::new(PieceType::Moewe, Team::Two, 2)),
+        ]);
+        assert_eq!(board.piece_count(), 2);
+        assert_eq!(board.pieces(), hashmap![
+            Vec2::new(7, 7) => Piece::new(PieceType::Moewe, Team::Two, 2),
+            Vec2::new(3, 0) => Piece::new(PieceType::Seestern, Team::One, 1)
+        ]);
+        assert_eq!(board[Vec2::new(7, 7)].count(), 2);
+        assert_eq!(board.get(Vec2::new(8, 7)), None);
+        assert_eq!(board.get(Vec2::new(0, 0)), None);
+        assert!((0..64).all(|i| Board::field_index(Board::field_pos(i)) == i));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_field_index_out_of_bounds() {
+        Board::field_index(Vec2::new(8, -1));
+    }
+
+    #[test]
+    fn test_moves_of() {
+        let board = Board::new(hashmap![
+            Vec2::new(0, 0) => Piece::new(PieceType::Herzmuschel, Team::One, 1),
+            Vec2::new(1, 1) => Piece::new(PieceType::Moewe, Team::One, 1),
+            Vec2::new(7, 3) => Piece::new(PieceType::Herzmuschel, Team::Two, 1),
+            Vec2::new(6, 4) => Piece::new(PieceType::Seestern, Team::One, 1)
+        ]);
+        assert_eq!(board.moves_of(Team::One).filter(|m| m.from() == Vec2::new(0, 0)).count(), 0);
+        assert_eq!(board.moves_of(Team::Two).collect::<Vec<_>>(), vec![
+            Move::new(Vec2::new(7, 3), Vec2::new(6, 4)),
+            Move::new(Vec2::new(7, 3), Vec2::new(6, 2)),
+        ]);
+        assert_eq!(board.moves_of(Team::One).count(), 4 + 5);
+    }
+
+    #[test]
+    fn test_initial() {
+        let board = Board::initial(42);
+        assert_eq!(board, Board::initial(42));
+        assert_eq!(board.piece_count(), 16);
+        for y in 0..8 {
+            let piece = board[Vec2::new(0, y)];
+            let mirrored = board[Vec2::new(7, 7 - y)];
+            assert_eq!((piece.team(), mirrored.team()), (Team::One, Team::Two));
+            assert_eq!(piece.piece_type(), mirrored.piece_type());
+        }
+        for piece_type in PieceType::ALL {
+            assert_eq!(board.iter_pieces().filter(|(_, p)| p.piece_type() == piece_type).count(), 4);
+        }
+        assert!((0..16).any(|seed| Board::initial(seed) != board));
+    }
+
+    #[test]
+    fn test_amber_gain() {
+        let board = Board::new(hashmap![
+            Vec2::new(1, 1) => Piece::new(PieceType::Robbe, Team::One, 2),
+            Vec2::new(3, 2) => Piece::new(PieceType::Moewe, Team::Two, 1),
+            Vec2::new(6, 5) => Piece::new(PieceType::Moewe, Team::One, 2)
+        ]);
+        let gain = |fx, fy, tx, ty| board.amber_gain(Move::new(Vec2::new(fx, fy), Vec2::new(tx, ty)));
+        assert_eq!(gain(1, 1, 3, 2), 1);
+        assert_eq!(gain(1, 1, 2, 3), 0);
+        assert_eq!(gain(6, 5, 7, 5), 1);
+        assert_eq!(gain(6, 5, 6, 6), 0);
+        assert!(board.is_capture(Move::new(Vec2::new(1, 1), Vec2::new(3, 2))));
+    }
 }
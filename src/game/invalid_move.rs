@@ -0,0 +1,33 @@
+use std::fmt;
+
+use super::{PieceType, Team, Vec2};
+
+/// A reason for which a move cannot be performed on a state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InvalidMove {
+    /// The game is already over.
+    GameOver,
+    /// There is no piece at the move's origin.
+    NoPieceAtOrigin(Vec2),
+    /// The piece at the origin belongs to the wrong team.
+    WrongTeam { expected: Team, actual: Team },
+    /// The move's destination is not on the board.
+    OutOfBounds(Vec2),
+    /// The piece type does not allow moving by the given delta.
+    DeltaNotAllowed { piece_type: PieceType, delta: Vec2 },
+    /// The destination is occupied by a piece of the moving team.
+    OwnPieceAtDestination(Vec2),
+}
+
+impl fmt::Display for InvalidMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidMove::GameOver => write!(f, "The game is already over"),
+            InvalidMove::NoPieceAtOrigin(pos) => write!(f, "There is no piece at {}", pos),
+            InvalidMove::WrongTeam { expected, actual } => write!(f, "The piece belongs to team {}, but team {} is moving", actual, expected),
+            InvalidMove::OutOfBounds(pos) => write!(f, "The destination {} is out of bounds", pos),
+            InvalidMove::DeltaNotAllowed { piece_type, delta } => write!(f, "A {} cannot move by {}", piece_type, delta),
+            InvalidMove::OwnPieceAtDestination(pos) => write!(f, "The destination {} is occupied by an own piece", pos),
+        }
+    }
+}
@@ -3,6 +3,14 @@ mod vec2;
 mod piece;
 mod r#move;
 mod piece_type;
+mod outcome;
+mod invalid_move;
+mod undo;
+mod render;
+mod perft;
+mod record;
+
+pub mod zobrist;
 mod state;
 mod team;
 
@@ -11,5 +19,11 @@ pub use vec2::*;
 pub use piece::*;
 pub use r#move::*;
 pub use piece_type::*;
+pub use outcome::*;
+pub use invalid_move::*;
+pub use undo::*;
+pub use render::*;
+pub use perft::*;
+pub use record::*;
 pub use state::*;
 pub use team::*;
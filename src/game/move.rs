@@ -25,6 +25,22 @@ impl Move {
 
     #[inline]
     pub fn delta(self) -> Vec2 { self.to - self.from }
+
+    /// The move in compact notation, i.e. the origin
+    /// followed by the destination (e.g. `a3b4`).
+    pub fn notation(self) -> String {
+        format!("{}{}", self.from.notation(), self.to.notation())
+    }
+
+    /// Parses a move in compact notation (e.g. `a3b4`).
+    pub fn from_notation(s: &str) -> SCResult<Self> {
+        let split = s.char_indices()
+            .skip(1)
+            .find(|(_, c)| c.is_ascii_alphabetic())
+            .map(|(i, _)| i)
+            .ok_or_else(|| SCError::InvalidNotation(format!("Invalid move {}", s)))?;
+        Ok(Self::new(Vec2::from_notation(&s[..split])?, Vec2::from_notation(&s[split..])?))
+    }
 }
 
 impl fmt::Display for Move {
@@ -72,4 +88,12 @@ mod tests {
             to: Vec2::new(5, 9),
         });
     }
+
+    #[test]
+    fn test_notation() {
+        let m = Move::new(Vec2::new(0, 2), Vec2::new(1, 3));
+        assert_eq!(m.notation(), "a3b4");
+        assert_eq!(Move::from_notation("a3b4").unwrap(), m);
+        assert!(Move::from_notation("a3").is_err());
+    }
 }
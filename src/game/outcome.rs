@@ -0,0 +1,69 @@
+use std::fmt;
+
+use super::Team;
+
+/// The reason for which a game ended the way it did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutcomeReason {
+    /// A team reached the winning number of ambers and has more ambers than its opponent.
+    AmberThreshold,
+    /// The round limit was reached and one team has more ambers.
+    RoundLimit,
+    /// Both teams have the same number of ambers, the team with
+    /// the further advanced light pieces wins.
+    Advancement,
+    /// The current team cannot move anymore and thus loses.
+    NoMovesLeft,
+    /// Neither ambers nor advancement could decide the game.
+    Draw,
+}
+
+/// The result of a finished game as determined by the rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The winning team, if any.
+    winner: Option<Team>,
+    /// The reason for the outcome.
+    reason: OutcomeReason,
+}
+
+impl Outcome {
+    /// Creates a new outcome.
+    #[inline]
+    pub fn new(winner: Option<Team>, reason: OutcomeReason) -> Self {
+        Self { winner, reason }
+    }
+
+    /// The winning team, if any.
+    #[inline]
+    pub fn winner(self) -> Option<Team> { self.winner }
+
+    /// The reason for the outcome.
+    #[inline]
+    pub fn reason(self) -> OutcomeReason { self.reason }
+
+    /// Whether the game ended without a winner.
+    #[inline]
+    pub fn is_draw(self) -> bool { self.winner.is_none() }
+}
+
+impl fmt::Display for OutcomeReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutcomeReason::AmberThreshold => write!(f, "amber threshold"),
+            OutcomeReason::RoundLimit => write!(f, "round limit"),
+            OutcomeReason::Advancement => write!(f, "advancement of light pieces"),
+            OutcomeReason::NoMovesLeft => write!(f, "no moves left"),
+            OutcomeReason::Draw => write!(f, "draw"),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.winner {
+            Some(team) => write!(f, "{} wins by {}", team, self.reason),
+            None => write!(f, "{}", self.reason),
+        }
+    }
+}
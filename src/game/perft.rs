@@ -0,0 +1,106 @@
+use super::{Move, State};
+
+/// Counts the leaf nodes of the game tree at the given depth below
+/// the given state. Nodes at which the game is over have no children.
+/// The state is walked in place and restored before returning.
+pub fn perft(state: &mut State, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    if state.is_over() {
+        return 0;
+    }
+    let mut moves = Vec::new();
+    state.fill_moves(&mut moves);
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    moves.into_iter()
+        .map(|m| {
+            let undo = state.perform(m);
+            let count = perft(state, depth - 1);
+            state.unmake(undo);
+            count
+        })
+        .sum()
+}
+
+/// Counts the leaf nodes at the given depth separately for each
+/// possible move at the root ('divide').
+pub fn perft_divide(state: &mut State, depth: usize) -> Vec<(Move, u64)> {
+    if depth == 0 || state.is_over() {
+        return Vec::new();
+    }
+    state.possible_moves()
+        .into_iter()
+        .map(|m| {
+            let undo = state.perform(m);
+            let count = perft(state, depth - 1);
+            state.unmake(undo);
+            (m, count)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::game::{perft, perft_divide, Board, State, Vec2, Move, BOARD_SIZE};
+
+    /// Leaf counts at depths 1 to 4 for a few start setups and a
+    /// middlegame position with towers. This is a regression snapshot
+    /// only: the counts were generated by this implementation, not by
+    /// the official game server, so they do not prove correctness.
+    /// TODO: Replace them with counts from the official plugin.
+    const SNAPSHOT: [(&str, [u64; 4]); 4] = [
+        ("R6r/H6s/M6m/H6s/S6h/M6m/S6h/R6r 0:0 0 1 -", [16, 256, 4160, 67600]),
+        ("H6r/R6s/S6h/M6m/M6m/H6s/S6r/R6h 0:0 0 1 -", [16, 256, 4352, 73984]),
+        ("M6m/S6r/S6h/R6h/H6r/H6s/R6s/M6m 0:0 0 1 -", [19, 361, 7068, 138384]),
+        ("5m2/HH5s/S6R[2]/8/8/MS[2]4s1/2R1r2h/M6h 1:0 20 1 f5e7", [22, 374, 8612, 152133]),
+    ];
+
+    /// Generates moves by trying all origin-destination pairs
+    /// against `State::validate_move`. It shares the movement rules
+    /// with the move generator, so it only checks the two code paths
+    /// for consistency.
+    fn brute_force_perft(state: &State, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let positions = || (0..BOARD_SIZE as i32).flat_map(|x| (0..BOARD_SIZE as i32).map(move |y| Vec2::new(x, y)));
+        positions()
+            .flat_map(|from| positions().map(move |to| Move::new(from, to)))
+            .filter(|&m| state.validate_move(m).is_ok())
+            .map(|m| brute_force_perft(&state.child(m), depth - 1))
+            .sum()
+    }
+
+    #[test]
+    fn test_snapshot_counts() {
+        for (notation, counts) in SNAPSHOT {
+            let mut state = State::from_str(notation).unwrap();
+            for (depth, &count) in counts.iter().enumerate() {
+                assert_eq!(perft(&mut state, depth + 1), count, "Perft of {} at depth {}", notation, depth + 1);
+            }
+            assert_eq!(state, State::from_str(notation).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_brute_force() {
+        for (notation, counts) in SNAPSHOT {
+            let state = State::from_str(notation).unwrap();
+            assert_eq!(brute_force_perft(&state, 2), counts[1], "Brute-force perft of {}", notation);
+        }
+    }
+
+    #[test]
+    fn test_divide() {
+        let mut state = State::initial(3);
+        let divide = perft_divide(&mut state, 3);
+        assert_eq!(divide.len(), state.possible_moves().len());
+        assert_eq!(divide.iter().map(|(_, n)| n).sum::<u64>(), perft(&mut state, 3));
+        assert_eq!(state.board(), &Board::initial(3));
+    }
+}
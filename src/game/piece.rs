@@ -2,6 +2,11 @@ use crate::util::{Element, SCError, SCResult};
 
 use super::{PieceType, Team, Vec2};
 
+/// The largest tower count accepted from the server or notation.
+/// Towers with more than two pieces never remain on the board, this
+/// bound only ensures that capturing cannot overflow the count.
+pub const MAX_PIECE_COUNT: usize = u8::MAX as usize / 2;
+
 /// A placeable figure on the board.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Piece {
@@ -10,12 +15,14 @@ pub struct Piece {
     /// Which team this piece belongs to.
     team: Team,
     /// Number of pieces in this tower.
-    count: usize,
+    count: u8,
 }
 
 impl Piece {
-    /// Creates a new piece.
+    /// Creates a new piece. Panics if the count
+    /// is too large for a tower.
     pub fn new(piece_type: PieceType, team: Team, count: usize) -> Self {
+        let count = u8::try_from(count).unwrap_or_else(|_| panic!("Tower count {} is out of range!", count));
         Self { piece_type, team, count }
     }
 
@@ -29,7 +36,7 @@ impl Piece {
 
     /// Number of pieces in this tower.
     #[inline]
-    pub fn count(self) -> usize { self.count }
+    pub fn count(self) -> usize { self.count as usize }
 
     /// Returns whether the piece can be turned into an amber.
     #[inline]
@@ -38,16 +45,23 @@ impl Piece {
     /// A new piece that captures the other piece.
     pub fn capture(self, other: Self) -> Self {
         let mut captured = self;
-        captured.count += other.count;
+        captured.count = self.count.checked_add(other.count).expect("Tower count overflowed!");
         captured
     }
 
+    /// The piece's letter, uppercase for the first team
+    /// and lowercase for the second team.
+    pub fn letter(self) -> char {
+        match self.team {
+            Team::One => self.piece_type.letter().to_ascii_uppercase(),
+            Team::Two => self.piece_type.letter(),
+        }
+    }
+
     /// The directions this piece can move in.
+    #[inline]
     pub fn possible_directions(self) -> impl Iterator<Item=Vec2> {
-        let direction = self.team.direction();
-        self.piece_type.possible_directions()
-            .into_iter()
-            .map(move |v| Vec2::new(v.x * direction, v.y))
+        self.piece_type.directions(self.team).iter().copied()
     }
 }
 
@@ -58,7 +72,10 @@ impl TryFrom<&Element> for Piece {
         Ok(Piece {
             piece_type: elem.attribute("type")?.parse()?,
             team: elem.attribute("team")?.parse()?,
-            count: elem.attribute("count")?.parse()?,
+            count: match elem.attribute("count")?.parse()? {
+                count @ 1..=MAX_PIECE_COUNT => count as u8,
+                count => return Err(SCError::InvalidState(format!("Invalid tower count {}", count))),
+            },
         })
     }
 }
@@ -78,5 +95,8 @@ mod tests {
             team: Team::Two,
             count: 1,
         });
+        assert!(Piece::try_from(&Element::from_str(r#"
+            <piece type="Herzmuschel" team="TWO" count="200" />
+        "#).unwrap()).is_err());
     }
 }
@@ -1,9 +1,40 @@
-use std::{str::FromStr, iter::once};
+use std::str::FromStr;
 use std::fmt;
 
 use crate::util::{SCError, SCResult};
 
-use super::{Vec2, CARDINALS, DIAGONALS};
+use super::{Team, Vec2, CARDINALS, DIAGONALS};
+
+const HERZMUSCHEL_DIRECTIONS: [Vec2; 2] = [Vec2 { x: 1, y: 1 }, Vec2 { x: 1, y: -1 }];
+const MOEWE_DIRECTIONS: [Vec2; 4] = CARDINALS;
+const SEESTERN_DIRECTIONS: [Vec2; 5] = [DIAGONALS[0], DIAGONALS[1], DIAGONALS[2], DIAGONALS[3], Vec2 { x: 1, y: 0 }];
+const ROBBE_DIRECTIONS: [Vec2; 8] = [
+    Vec2 { x: -2, y: -1 },
+    Vec2 { x: -1, y: -2 },
+    Vec2 { x: -2, y:  1 },
+    Vec2 { x: -1, y:  2 },
+    Vec2 { x:  2, y: -1 },
+    Vec2 { x:  1, y: -2 },
+    Vec2 { x:  2, y:  1 },
+    Vec2 { x:  1, y:  2 },
+];
+
+/// Mirrors the given directions along the x-axis, i.e.
+/// turns them into the directions of the second team.
+const fn mirrored<const N: usize>(directions: [Vec2; N]) -> [Vec2; N] {
+    let mut result = directions;
+    let mut i = 0;
+    while i < N {
+        result[i].x = -result[i].x;
+        i += 1;
+    }
+    result
+}
+
+const HERZMUSCHEL_DIRECTIONS_TWO: [Vec2; 2] = mirrored(HERZMUSCHEL_DIRECTIONS);
+const MOEWE_DIRECTIONS_TWO: [Vec2; 4] = mirrored(MOEWE_DIRECTIONS);
+const SEESTERN_DIRECTIONS_TWO: [Vec2; 5] = mirrored(SEESTERN_DIRECTIONS);
+const ROBBE_DIRECTIONS_TWO: [Vec2; 8] = mirrored(ROBBE_DIRECTIONS);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PieceType {
@@ -18,22 +49,63 @@ pub enum PieceType {
 }
 
 impl PieceType {
+    /// All piece types.
+    pub const ALL: [PieceType; 4] = [Self::Herzmuschel, Self::Moewe, Self::Seestern, Self::Robbe];
+
+    /// The piece type's index in `PieceType::ALL`.
+    #[inline]
+    pub fn index(self) -> usize {
+        match self {
+            Self::Herzmuschel => 0,
+            Self::Moewe => 1,
+            Self::Seestern => 2,
+            Self::Robbe => 3,
+        }
+    }
+
     /// Checks whether a piece is lightweight. Only the 'robbe' is non-light.
     #[inline]
     pub fn is_light(self) -> bool {
         !matches!(self, Self::Robbe)
     }
 
-    /// The directions in which this piece is allowed to move.
-    pub fn possible_directions(self) -> Vec<Vec2> {
+    /// The (lowercase) letter used to denote this piece type in notation.
+    pub fn letter(self) -> char {
         match self {
-            Self::Herzmuschel => vec![Vec2::new(1, 1), Vec2::new(1, -1)],
-            Self::Moewe => CARDINALS.into_iter().collect(),
-            Self::Seestern => DIAGONALS.into_iter().chain(once(Vec2::new(1, 0))).collect(),
-            Self::Robbe => DIAGONALS.into_iter().flat_map(|v| [
-                Vec2::new(2 * v.x, v.y),
-                Vec2::new(v.x, 2 * v.y),
-            ].into_iter()).collect(),
+            Self::Herzmuschel => 'h',
+            Self::Moewe => 'm',
+            Self::Seestern => 's',
+            Self::Robbe => 'r',
+        }
+    }
+
+    /// Parses a piece type from its (case-insensitive) letter.
+    pub fn from_letter(c: char) -> SCResult<Self> {
+        Self::ALL.into_iter()
+            .find(|t| t.letter() == c.to_ascii_lowercase())
+            .ok_or_else(|| SCError::InvalidNotation(format!("Unknown piece letter {}", c)))
+    }
+
+    /// The directions in which this piece is allowed to move
+    /// from the perspective of the first team.
+    #[inline]
+    pub fn possible_directions(self) -> &'static [Vec2] {
+        self.directions(Team::One)
+    }
+
+    /// The directions in which this piece is allowed to move
+    /// when owned by the given team.
+    #[inline]
+    pub fn directions(self, team: Team) -> &'static [Vec2] {
+        match (self, team) {
+            (Self::Herzmuschel, Team::One) => &HERZMUSCHEL_DIRECTIONS,
+            (Self::Herzmuschel, Team::Two) => &HERZMUSCHEL_DIRECTIONS_TWO,
+            (Self::Moewe, Team::One) => &MOEWE_DIRECTIONS,
+            (Self::Moewe, Team::Two) => &MOEWE_DIRECTIONS_TWO,
+            (Self::Seestern, Team::One) => &SEESTERN_DIRECTIONS,
+            (Self::Seestern, Team::Two) => &SEESTERN_DIRECTIONS_TWO,
+            (Self::Robbe, Team::One) => &ROBBE_DIRECTIONS,
+            (Self::Robbe, Team::Two) => &ROBBE_DIRECTIONS_TWO,
         }
     }
 }
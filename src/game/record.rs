@@ -0,0 +1,124 @@
+use std::{fmt, str::FromStr};
+
+use crate::util::{SCError, SCResult};
+
+use super::{Move, Outcome, State};
+
+/// A game as a start state and the moves played from it,
+/// e.g. for building opening books or tuning evaluations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    start: State,
+    moves: Vec<Move>,
+}
+
+impl GameRecord {
+    /// Creates a record without moves.
+    pub fn new(start: State) -> Self {
+        Self { start, moves: Vec::new() }
+    }
+
+    /// Records a game in which moves are picked by the given function
+    /// until the game is over.
+    pub fn play(start: State, mut choose: impl FnMut(&State) -> Move) -> Self {
+        let mut record = Self::new(start);
+        let mut state = record.start.clone();
+        while !state.is_over() {
+            let m = choose(&state);
+            state.perform(m);
+            record.moves.push(m);
+        }
+        record
+    }
+
+    /// The state the game started from.
+    #[inline]
+    pub fn start(&self) -> &State { &self.start }
+
+    /// The moves played.
+    #[inline]
+    pub fn moves(&self) -> &[Move] { &self.moves }
+
+    /// Appends a played move.
+    pub fn push(&mut self, m: Move) {
+        self.moves.push(m);
+    }
+
+    /// The states of the game along with the move played in them.
+    pub fn positions(&self) -> impl Iterator<Item=(State, Move)> + '_ {
+        self.moves.iter().scan(self.start.clone(), |state, &m| {
+            let before = state.clone();
+            state.perform(m);
+            Some((before, m))
+        })
+    }
+
+    /// The state after all moves.
+    pub fn final_state(&self) -> State {
+        let mut state = self.start.clone();
+        for &m in &self.moves {
+            state.perform(m);
+        }
+        state
+    }
+
+    /// The outcome of the game, if it is over.
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.final_state().outcome()
+    }
+}
+
+/// Formats the record on a single line as the start
+/// state's notation followed by `;` and the moves.
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{};", self.start)?;
+        for m in &self.moves {
+            write!(f, " {}", m.notation())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = SCError;
+
+    fn from_str(s: &str) -> SCResult<Self> {
+        let (start, moves) = s.split_once(';')
+            .ok_or_else(|| SCError::InvalidNotation(format!("Expected 'state; moves', got '{}'", s)))?;
+        let mut record = Self::new(State::from_str(start.trim())?);
+        let mut state = record.start.clone();
+        for notation in moves.split_whitespace() {
+            let m = Move::from_notation(notation)?;
+            state.try_perform(m)?;
+            record.push(m);
+        }
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::game::{GameRecord, State};
+
+    #[test]
+    fn test_play() {
+        let record = GameRecord::play(State::initial(4), |state| state.moves().last().unwrap());
+        let state = record.final_state();
+        assert!(state.is_over());
+        assert_eq!(state.turn(), record.moves().len());
+        assert_eq!(record.outcome(), state.outcome());
+        assert_eq!(record.positions().count(), record.moves().len());
+        assert!(record.positions().all(|(state, m)| state.validate_move(m).is_ok()));
+    }
+
+    #[test]
+    fn test_notation() {
+        let record = GameRecord::play(State::initial(5), |state| state.moves().next().unwrap());
+        let notation = record.to_string();
+        assert_eq!(GameRecord::from_str(&notation).unwrap(), record);
+        assert!(GameRecord::from_str("R6r/H6s/M6m/H6s/S6h/M6m/S6h/R6r 0:0 0 1 -; a1a2").is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+
+use super::{Board, Move, State, Team, Vec2, BOARD_SIZE};
+
+/// A human-readable rendering of a board (and optionally the state
+/// around it) as an 8x8 grid, e.g. for logging or terminals.
+///
+/// Pieces are shown by their letter (uppercase for the first team),
+/// followed by the height of the tower if it has more than one piece.
+/// Highlighted fields are enclosed in brackets.
+#[derive(Debug, Clone, Copy)]
+pub struct Render<'a> {
+    board: &'a Board,
+    state: Option<&'a State>,
+    colored: bool,
+    highlight: Option<Move>,
+}
+
+impl<'a> Render<'a> {
+    /// Creates a plain rendering of the given board.
+    pub fn new(board: &'a Board) -> Self {
+        Self { board, state: None, colored: false, highlight: None }
+    }
+
+    /// Creates a rendering of the given state, including a status
+    /// line and highlighting the last move.
+    pub fn of_state(state: &'a State) -> Self {
+        Self { board: state.board(), state: Some(state), colored: false, highlight: state.last_move() }
+    }
+
+    /// Sets whether to use ANSI colors for the teams.
+    pub fn colored(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+
+    /// Sets the move whose origin and destination are highlighted.
+    pub fn highlight(mut self, highlight: Option<Move>) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    fn color_code(team: Team) -> &'static str {
+        match team {
+            Team::One => "1;31",
+            Team::Two => "1;34",
+        }
+    }
+}
+
+impl fmt::Display for Render<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "   ")?;
+        for x in 0..BOARD_SIZE {
+            write!(f, " {}  ", (b'a' + x as u8) as char)?;
+        }
+        for y in 0..BOARD_SIZE as i32 {
+            write!(f, "\n{:>2} ", y + 1)?;
+            for x in 0..BOARD_SIZE as i32 {
+                let pos = Vec2::new(x, y);
+                let highlighted = self.highlight.is_some_and(|m| m.from() == pos || m.to() == pos);
+                let (open, close) = if highlighted { ('[', ']') } else { (' ', ' ') };
+                write!(f, "{}", open)?;
+                match self.board.get(pos) {
+                    Some(piece) => {
+                        if self.colored {
+                            write!(f, "\x1b[{}m", Self::color_code(piece.team()))?;
+                        }
+                        write!(f, "{}", piece.letter())?;
+                        if piece.count() > 1 {
+                            write!(f, "{}", piece.count())?;
+                        } else {
+                            write!(f, " ")?;
+                        }
+                        if self.colored {
+                            write!(f, "\x1b[0m")?;
+                        }
+                    },
+                    None => write!(f, ". ")?,
+                }
+                write!(f, "{}", close)?;
+            }
+        }
+        if let Some(state) = self.state {
+            write!(f, "\nTurn {} (round {})", state.turn(), state.round())?;
+            if let Some(team) = state.current_team() {
+                write!(f, ", {} to move", team)?;
+            }
+            write!(f, ", ambers {}:{}", state.ambers_of(Team::One), state.ambers_of(Team::Two))?;
+            if let Some(m) = state.last_move() {
+                write!(f, ", last move {}", m.notation())?;
+            }
+            if let Some(outcome) = state.outcome() {
+                write!(f, ", game over: {}", outcome)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{game::{Board, Move, Piece, PieceType, Render, State, Team, Vec2}, hashmap};
+
+    #[test]
+    fn test_render() {
+        let board = Board::new(hashmap![
+            Vec2::new(0, 0) => Piece::new(PieceType::Herzmuschel, Team::One, 2),
+            Vec2::new(1, 1) => Piece::new(PieceType::Robbe, Team::Two, 1)
+        ]);
+        let rendered = Render::new(&board)
+            .highlight(Some(Move::new(Vec2::new(1, 1), Vec2::new(2, 2))))
+            .to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[0], "    a   b   c   d   e   f   g   h  ");
+        assert_eq!(lines[1], " 1  H2  .   .   .   .   .   .   .  ");
+        assert_eq!(lines[2], " 2  .  [r ] .   .   .   .   .   .  ");
+        assert_eq!(lines[3], " 3  .   .  [. ] .   .   .   .   .  ");
+        assert_eq!(board.to_string(), Render::new(&board).to_string());
+        assert!(Render::new(&board).colored(true).to_string().contains("\x1b[1;34mr \x1b[0m"));
+    }
+
+    #[test]
+    fn test_render_state() {
+        let mut state = State::initial(1);
+        let m = state.possible_moves()[0];
+        state.perform(m);
+        let rendered = format!("{:#}", state);
+        assert!(rendered.contains(&format!("Turn 1 (round 1), TWO to move, ambers 0:0, last move {}", m.notation())));
+        assert_eq!(rendered.matches('[').count(), 2);
+    }
+}
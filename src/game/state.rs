@@ -1,10 +1,12 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, str::FromStr, cmp::Ordering, hash::{Hash, Hasher}, fmt};
 
 use crate::util::{Element, SCError, SCResult};
 
-use super::{Board, Move, Team, Piece, Vec2};
+use super::{Board, Move, Team, Piece, PieceType, Vec2, Render, BOARD_SIZE, Outcome, OutcomeReason, InvalidMove, Undo, MAX_PIECE_COUNT, zobrist};
 
+/// The number of rounds, i.e. moves per team, after which the game ends.
 pub const ROUND_LIMIT: usize = 30;
+pub const WINNING_AMBERS: usize = 2;
 
 /// The state of the game at a point in time.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -19,13 +21,33 @@ pub struct State {
     last_move: Option<Move>,
     /// The starting team.
     start_team: Option<Team>,
+    /// The Zobrist hash of the state, updated incrementally.
+    hash: u64,
 }
 
 impl State {
+    /// Creates a new state.
+    pub fn new(board: Board, ambers: impl Into<HashMap<Team, usize>>, turn: usize, last_move: Option<Move>, start_team: Option<Team>) -> Self {
+        let mut state = Self { board, ambers: ambers.into(), turn, last_move, start_team, hash: 0 };
+        state.hash = state.compute_hash();
+        state
+    }
+
+    /// Creates the start state of a game from the given seed,
+    /// see `Board::initial`. The first team starts.
+    pub fn initial(seed: u64) -> Self {
+        Self::new(Board::initial(seed), [(Team::One, 0), (Team::Two, 0)], 0, None, Some(Team::One))
+    }
+
     /// The game board.
     #[inline]
     pub fn board(&self) -> &Board { &self.board }
 
+    /// A human-readable rendering of the state, which can be customized
+    /// further (e.g. with colors). The alternate format `{:#}` yields
+    /// the default rendering.
+    pub fn render(&self) -> Render<'_> { Render::of_state(self) }
+
     /// The ambers per team.
     #[inline]
     pub fn ambers(&self) -> &HashMap<Team, usize> { &self.ambers }
@@ -34,9 +56,9 @@ impl State {
     #[inline]
     pub fn turn(&self) -> usize { self.turn }
 
-    /// Fetches the round, i.e. `(turn + 1) / 2`.
+    /// Fetches the (1-based) round, i.e. `1 + turn / 2`, as the server does.
     #[inline]
-    pub fn round(&self) -> usize { (self.turn + 1) / 2 }
+    pub fn round(&self) -> usize { 1 + self.turn / 2 }
 
     /// The most recent move, if available.
     #[inline]
@@ -46,10 +68,30 @@ impl State {
     #[inline]
     pub fn start_team(&self) -> Option<Team> { self.start_team }
 
+    /// The Zobrist hash of the position, covering the pieces, the
+    /// ambers and the side to move (but not the turn count or the
+    /// last move).
+    #[inline]
+    pub fn zobrist(&self) -> u64 { self.hash }
+
+    /// Computes the Zobrist hash from scratch.
+    fn compute_hash(&self) -> u64 {
+        let mut hash = zobrist::board_key(&self.board)
+            ^ zobrist::ambers_key(Team::One, self.ambers_of(Team::One))
+            ^ zobrist::ambers_key(Team::Two, self.ambers_of(Team::Two));
+        if self.turn % 2 == 1 {
+            hash ^= zobrist::side_key();
+        }
+        if self.start_team == Some(Team::Two) {
+            hash ^= zobrist::second_team_starts_key();
+        }
+        hash
+    }
+
     /// The current team, computed from the starting team and the turn.
     pub fn current_team(&self) -> Option<Team> {
         let start_team = self.start_team?;
-        Some(if self.turn % 2 == 0 { start_team } else { start_team.opponent() })
+        Some(if self.turn.is_multiple_of(2) { start_team } else { start_team.opponent() })
     }
 
     // Partially translated from https://github.com/software-challenge/backend/blob/89407e5e2f76801ec8beb8f31412da218f5f70e5/plugin/src/main/kotlin/sc/plugin2022/GameState.kt
@@ -57,33 +99,178 @@ impl State {
     /// Fetches the current team's pieces.
     pub fn current_pieces<'a>(&'a self) -> impl Iterator<Item=(Vec2, Piece)> + 'a {
         let team = self.current_team();
-        self.board.pieces()
-            .iter()
+        self.board.iter_pieces()
             .filter(move |&(_, piece)| Some(piece.team()) == team)
-            .map(|(&pos, &piece)| (pos, piece))
+    }
+
+    /// Lazily generates the possible moves without allocating.
+    pub fn moves(&self) -> impl Iterator<Item=Move> + '_ {
+        self.current_team()
+            .into_iter()
+            .flat_map(move |team| self.board.moves_of(team))
+    }
+
+    /// Lazily generates the capturing moves and the moves earning an
+    /// amber, i.e. the tactical moves considered by a quiescence search.
+    pub fn capture_moves(&self) -> impl Iterator<Item=Move> + '_ {
+        self.moves().filter(move |&m| self.board.is_capture(m) || self.board.amber_gain(m) > 0)
+    }
+
+    /// Fills the given buffer with the possible moves, replacing its
+    /// previous contents. Reusing the buffer avoids allocations.
+    pub fn fill_moves(&self, moves: &mut Vec<Move>) {
+        moves.clear();
+        moves.extend(self.moves());
     }
 
     /// Fetches the possible moves.
     pub fn possible_moves(&self) -> Vec<Move> {
-        self.current_pieces()
-            .flat_map(|(pos, piece)| piece.possible_directions()
-                .map(move |delta| Move::new(pos, pos + delta))
-                .filter(move |m| Board::is_in_bounds(m.to()) && self.board.get(m.to()).map(|p| p.team()) != Some(piece.team())))
-            .collect()
+        self.moves().collect()
+    }
+
+    /// Fetches the number of ambers of the given team.
+    pub fn ambers_of(&self, team: Team) -> usize {
+        self.ambers.get(&team).cloned().unwrap_or(0)
     }
 
     /// Checks whether the game is over.
     pub fn is_over(&self) -> bool {
-        self.turn % 2 == 0 && (self.round() > ROUND_LIMIT || self.ambers.iter().any(|(_, &v)| v >= 2))
+        self.is_over_by_rounds_or_ambers() || (self.current_team().is_some() && !self.has_possible_moves())
+    }
+
+    /// Checks whether the game ended regularly, i.e. at the end of a round
+    /// after either the round limit or the amber threshold was reached.
+    fn is_over_by_rounds_or_ambers(&self) -> bool {
+        self.turn.is_multiple_of(2) && (self.round() > ROUND_LIMIT || self.ambers.iter().any(|(_, &v)| v >= WINNING_AMBERS))
+    }
+
+    /// Checks whether the current team has at least one move.
+    fn has_possible_moves(&self) -> bool {
+        self.moves().next().is_some()
+    }
+
+    /// The distances of the team's light pieces from its start line,
+    /// sorted in descending order. Used to break ties.
+    pub fn advancement(&self, team: Team) -> Vec<i32> {
+        let mut distances: Vec<i32> = self.board.iter_pieces()
+            .filter(|(_, piece)| piece.team() == team && piece.piece_type().is_light())
+            .map(|(pos, _)| (pos.x - Board::start_line(team)).abs())
+            .collect();
+        distances.sort_unstable_by(|a, b| b.cmp(a));
+        distances
+    }
+
+    /// Determines the outcome of the game, if it is over. This mirrors
+    /// the server: The team with more ambers wins, ties are broken by
+    /// comparing the advancement of the light pieces (the furthest
+    /// advanced first). A team that cannot move loses.
+    pub fn outcome(&self) -> Option<Outcome> {
+        if self.is_over_by_rounds_or_ambers() {
+            let ambers = (self.ambers_of(Team::One), self.ambers_of(Team::Two));
+            let reason = if ambers.0 >= WINNING_AMBERS || ambers.1 >= WINNING_AMBERS {
+                OutcomeReason::AmberThreshold
+            } else {
+                OutcomeReason::RoundLimit
+            };
+            Some(match ambers.0.cmp(&ambers.1) {
+                Ordering::Greater => Outcome::new(Some(Team::One), reason),
+                Ordering::Less => Outcome::new(Some(Team::Two), reason),
+                Ordering::Equal => match self.advancement(Team::One).cmp(&self.advancement(Team::Two)) {
+                    Ordering::Greater => Outcome::new(Some(Team::One), OutcomeReason::Advancement),
+                    Ordering::Less => Outcome::new(Some(Team::Two), OutcomeReason::Advancement),
+                    Ordering::Equal => Outcome::new(None, OutcomeReason::Draw),
+                },
+            })
+        } else {
+            let team = self.current_team()?;
+            if self.has_possible_moves() {
+                None
+            } else {
+                Some(Outcome::new(Some(team.opponent()), OutcomeReason::NoMovesLeft))
+            }
+        }
+    }
+
+    /// The winner of the game, if it is over and not a draw.
+    pub fn winner(&self) -> Option<Team> {
+        self.outcome().and_then(|o| o.winner())
+    }
+
+    /// Performs the given move, i.e. moves (and possibly stacks) the piece,
+    /// converts towers and pieces on the opponent's start line to ambers,
+    /// credits them to the moving team and advances the turn. Returns
+    /// the information needed to revert the move using `unmake`.
+    pub fn perform(&mut self, m: Move) -> Undo {
+        let hash = self.hash;
+        let team = self.board[m.from()].team();
+        let board = self.board.perform(m);
+        self.hash ^= zobrist::piece_key(m.from(), board.piece);
+        if let Some(captured) = board.captured {
+            self.hash ^= zobrist::piece_key(m.to(), captured);
+        }
+        let ambers = self.board.check_amber(m.to());
+        if let Some(piece) = self.board.get(m.to()) {
+            self.hash ^= zobrist::piece_key(m.to(), piece);
+        }
+        let had_ambers = self.ambers.contains_key(&team);
+        if ambers > 0 {
+            let previous = self.ambers_of(team);
+            self.hash ^= zobrist::ambers_key(team, previous) ^ zobrist::ambers_key(team, previous + ambers);
+            *self.ambers.entry(team).or_insert(0) += ambers;
+        }
+        let last_move = self.last_move.replace(m);
+        self.turn += 1;
+        self.hash ^= zobrist::side_key();
+        Undo { board, team, ambers, had_ambers, last_move, hash }
+    }
+
+    /// Reverts the most recently performed move.
+    pub fn unmake(&mut self, undo: Undo) {
+        self.board.unmake(undo.board);
+        if undo.ambers > 0 {
+            if undo.had_ambers {
+                *self.ambers.entry(undo.team).or_insert(0) -= undo.ambers;
+            } else {
+                self.ambers.remove(&undo.team);
+            }
+        }
+        self.last_move = undo.last_move;
+        self.turn -= 1;
+        self.hash = undo.hash;
+    }
+
+    /// Checks whether the given move is valid in this state.
+    pub fn validate_move(&self, m: Move) -> Result<(), InvalidMove> {
+        if self.is_over() {
+            return Err(InvalidMove::GameOver);
+        }
+        let piece = self.board.get(m.from()).ok_or(InvalidMove::NoPieceAtOrigin(m.from()))?;
+        if let Some(team) = self.current_team() {
+            if piece.team() != team {
+                return Err(InvalidMove::WrongTeam { expected: team, actual: piece.team() });
+            }
+        }
+        if !Board::is_in_bounds(m.to()) {
+            return Err(InvalidMove::OutOfBounds(m.to()));
+        }
+        if !piece.possible_directions().any(|v| v == m.delta()) {
+            return Err(InvalidMove::DeltaNotAllowed { piece_type: piece.piece_type(), delta: m.delta() });
+        }
+        if self.board.get(m.to()).map(|p| p.team()) == Some(piece.team()) {
+            return Err(InvalidMove::OwnPieceAtDestination(m.to()));
+        }
+        Ok(())
     }
 
-    /// Performs the given move.
-    pub fn perform(&mut self, m: Move) {
-        self.board.perform(m);
+    /// Performs the given move if it is valid, otherwise
+    /// leaves the state untouched.
+    pub fn try_perform(&mut self, m: Move) -> Result<Undo, InvalidMove> {
+        self.validate_move(m)?;
+        Ok(self.perform(m))
     }
 
     /// Fetches the child state after the given move.
-    pub fn child(self, m: Move) -> State {
+    pub fn child(&self, m: Move) -> State {
         let mut child = self.clone();
         child.perform(m);
         child
@@ -94,9 +281,9 @@ impl TryFrom<&Element> for State {
     type Error = SCError;
 
     fn try_from(elem: &Element) -> SCResult<Self> {
-        Ok(State {
-            board: elem.child_by_name("board")?.try_into()?,
-            ambers: elem
+        Ok(State::new(
+            elem.child_by_name("board")?.try_into()?,
+            elem
                 .child_by_name("ambers")?
                 .childs_by_name("entry")
                 .map(|e| {
@@ -104,19 +291,152 @@ impl TryFrom<&Element> for State {
                     let piece = usize::from_str(e.child_by_name("int")?.content())?;
                     Ok((team, piece))
                 })
-                .collect::<SCResult<_>>()?,
-            turn: elem.attribute("turn")?.parse()?,
-            last_move: elem.child_by_name("lastMove").ok().and_then(|m| m.try_into().ok()),
-            start_team: elem.child_by_name("startTeam").ok().and_then(|t| t.content().parse().ok()),
-        })
+                .collect::<SCResult<HashMap<_, _>>>()?,
+            elem.attribute("turn")?.parse()?,
+            elem.child_by_name("lastMove").ok().and_then(|m| m.try_into().ok()),
+            elem.child_by_name("startTeam").ok().and_then(|t| t.content().parse().ok()),
+        ))
+    }
+}
+
+/// Formats the state in a compact, FEN-like notation consisting of
+/// five space-separated fields:
+///
+/// - The board as rows from `y = 0` to `y = 7` separated by `/`. Within a row,
+///   pieces are denoted by their letter (uppercase for the first team), followed
+///   by `[n]` for towers of height `n`, and runs of empty fields by their length.
+/// - The ambers of both teams, e.g. `1:0`.
+/// - The turn.
+/// - The starting team (`1`, `2` or `-`).
+/// - The last move in compact notation or `-`.
+///
+/// The alternate format `{:#}` renders the state as a grid instead.
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.render());
+        }
+        for y in 0..BOARD_SIZE as i32 {
+            if y > 0 {
+                write!(f, "/")?;
+            }
+            let mut empty = 0;
+            for x in 0..BOARD_SIZE as i32 {
+                match self.board.get(Vec2::new(x, y)) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            write!(f, "{}", empty)?;
+                            empty = 0;
+                        }
+                        write!(f, "{}", piece.letter())?;
+                        if piece.count() > 1 {
+                            write!(f, "[{}]", piece.count())?;
+                        }
+                    },
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                write!(f, "{}", empty)?;
+            }
+        }
+        write!(f, " {}:{} {}", self.ambers_of(Team::One), self.ambers_of(Team::Two), self.turn)?;
+        match self.start_team {
+            Some(team) => write!(f, " {}", team.index() + 1)?,
+            None => write!(f, " -")?,
+        }
+        match self.last_move {
+            Some(m) => write!(f, " {}", m.notation()),
+            None => write!(f, " -"),
+        }
+    }
+}
+
+impl FromStr for State {
+    type Err = SCError;
+
+    fn from_str(s: &str) -> SCResult<Self> {
+        let invalid = SCError::InvalidNotation;
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid(format!("Expected 5 fields, got {} in '{}'", fields.len(), s)));
+        }
+
+        let rows: Vec<&str> = fields[0].split('/').collect();
+        if rows.len() != BOARD_SIZE {
+            return Err(invalid(format!("Expected {} rows, got {}", BOARD_SIZE, rows.len())));
+        }
+        let mut board = Board::empty();
+        for (y, row) in rows.into_iter().enumerate() {
+            let mut x = 0;
+            let mut chars = row.chars().peekable();
+            while let Some(c) = chars.next() {
+                if let Some(empty) = c.to_digit(10) {
+                    if !(1..=BOARD_SIZE as u32).contains(&empty) {
+                        return Err(invalid(format!("Invalid number of empty fields {} in row {}", empty, y)));
+                    }
+                    x += empty as usize;
+                    continue;
+                }
+                let team = if c.is_ascii_uppercase() { Team::One } else { Team::Two };
+                let piece_type = PieceType::from_letter(c)?;
+                let mut count = 1;
+                if chars.peek() == Some(&'[') {
+                    chars.next();
+                    let digits: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                    count = digits.parse()?;
+                    if !(1..=MAX_PIECE_COUNT).contains(&count) {
+                        return Err(invalid(format!("Invalid tower count {} in row {}", count, y)));
+                    }
+                }
+                if x >= BOARD_SIZE {
+                    return Err(invalid(format!("Row {} is too long", y)));
+                }
+                board.set(Vec2::new(x as i32, y as i32), Some(Piece::new(piece_type, team, count)));
+                x += 1;
+            }
+            if x != BOARD_SIZE {
+                return Err(invalid(format!("Row {} has {} instead of {} fields", y, x, BOARD_SIZE)));
+            }
+        }
+
+        let (ambers_one, ambers_two) = fields[1].split_once(':')
+            .ok_or_else(|| invalid(format!("Invalid ambers {}", fields[1])))?;
+        let ambers = [(Team::One, ambers_one.parse()?), (Team::Two, ambers_two.parse()?)];
+        let turn = fields[2].parse()?;
+        let start_team = match fields[3] {
+            "1" => Some(Team::One),
+            "2" => Some(Team::Two),
+            "-" => None,
+            t => return Err(invalid(format!("Invalid start team {}", t))),
+        };
+        let last_move = match fields[4] {
+            "-" => None,
+            m => Some(Move::from_notation(m)?),
+        };
+
+        Ok(State::new(board, ambers, turn, last_move, start_team))
+    }
+}
+
+impl Hash for State {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{collections::HashMap, str::FromStr};
+
+    use crate::{util::Element, game::{Board, State, Team, Piece, PieceType, Vec2, Move, Outcome, OutcomeReason, InvalidMove, ROUND_LIMIT}, hashmap};
 
-    use crate::{util::Element, game::{Board, State, Team}, hashmap};
+    fn state_with(board: Board) -> State {
+        State::new(board, hashmap![
+            Team::One => 0usize,
+            Team::Two => 0usize
+        ], 0, None, Some(Team::One))
+    }
 
     #[test]
     fn test_parsing() {
@@ -136,15 +456,230 @@ mod tests {
                     </entry>
                 </ambers>
             </state>
-        "#).unwrap()).unwrap(), State {
-            board: Board::empty(),
-            ambers: hashmap![
-                Team::One => 1usize,
-                Team::Two => 0usize
-            ],
-            last_move: None,
-            start_team: None,
-            turn: 3,
-        });
+        "#).unwrap()).unwrap(), State::new(Board::empty(), hashmap![
+            Team::One => 1usize,
+            Team::Two => 0usize
+        ], 3, None, None));
+    }
+
+    #[test]
+    fn test_perform_capture() {
+        let mut state = state_with(Board::new(hashmap![
+            Vec2::new(3, 3) => Piece::new(PieceType::Moewe, Team::One, 1),
+            Vec2::new(4, 3) => Piece::new(PieceType::Herzmuschel, Team::Two, 1)
+        ]));
+        let m = Move::new(Vec2::new(3, 3), Vec2::new(4, 3));
+        state.perform(m);
+        assert_eq!(state.board(), &Board::new(hashmap![
+            Vec2::new(4, 3) => Piece::new(PieceType::Moewe, Team::One, 2)
+        ]));
+        assert_eq!(state.ambers()[&Team::One], 0);
+        assert_eq!(state.turn(), 1);
+        assert_eq!(state.last_move(), Some(m));
+        assert_eq!(state.current_team(), Some(Team::Two));
+    }
+
+    #[test]
+    fn test_perform_tower_amber() {
+        let mut state = state_with(Board::new(hashmap![
+            Vec2::new(1, 1) => Piece::new(PieceType::Robbe, Team::One, 2),
+            Vec2::new(3, 2) => Piece::new(PieceType::Moewe, Team::Two, 1)
+        ]));
+        state.perform(Move::new(Vec2::new(1, 1), Vec2::new(3, 2)));
+        assert_eq!(state.board(), &Board::empty());
+        assert_eq!(state.ambers()[&Team::One], 1);
+        assert_eq!(state.ambers()[&Team::Two], 0);
+    }
+
+    #[test]
+    fn test_perform_start_line_amber() {
+        let mut state = state_with(Board::new(hashmap![
+            Vec2::new(3, 3) => Piece::new(PieceType::Moewe, Team::One, 1),
+            Vec2::new(1, 4) => Piece::new(PieceType::Seestern, Team::Two, 2)
+        ]));
+        state.perform(Move::new(Vec2::new(3, 3), Vec2::new(3, 4)));
+        state.perform(Move::new(Vec2::new(1, 4), Vec2::new(0, 4)));
+        assert_eq!(state.board(), &Board::new(hashmap![
+            Vec2::new(3, 4) => Piece::new(PieceType::Moewe, Team::One, 1)
+        ]));
+        assert_eq!(state.ambers()[&Team::Two], 1);
+        assert_eq!(state.turn(), 2);
+    }
+
+    #[test]
+    fn test_outcome() {
+        let mut state = state_with(Board::new(hashmap![
+            Vec2::new(3, 3) => Piece::new(PieceType::Moewe, Team::One, 1),
+            Vec2::new(5, 5) => Piece::new(PieceType::Moewe, Team::Two, 1)
+        ]));
+        assert_eq!(state.outcome(), None);
+
+        state.ambers.insert(Team::Two, 2);
+        assert_eq!(state.outcome(), Some(Outcome::new(Some(Team::Two), OutcomeReason::AmberThreshold)));
+
+        state.ambers.insert(Team::Two, 0);
+        state.turn = 2 * ROUND_LIMIT;
+        assert_eq!(state.outcome(), Some(Outcome::new(Some(Team::One), OutcomeReason::Advancement)));
+
+        state.board = Board::new(hashmap![
+            Vec2::new(4, 3) => Piece::new(PieceType::Robbe, Team::One, 1),
+            Vec2::new(3, 5) => Piece::new(PieceType::Robbe, Team::Two, 1)
+        ]);
+        assert_eq!(state.outcome(), Some(Outcome::new(None, OutcomeReason::Draw)));
+    }
+
+    #[test]
+    fn test_round_limit() {
+        // Each team moves ROUND_LIMIT times, so the game ends
+        // at the start of the round after the limit
+        let mut state = state_with(Board::new(hashmap![
+            Vec2::new(3, 3) => Piece::new(PieceType::Moewe, Team::One, 1),
+            Vec2::new(5, 5) => Piece::new(PieceType::Moewe, Team::Two, 1)
+        ]));
+        assert_eq!(state.round(), 1);
+        state.turn = 2 * ROUND_LIMIT - 1;
+        assert_eq!(state.round(), ROUND_LIMIT);
+        assert!(!state.is_over());
+        state.perform(Move::new(Vec2::new(5, 5), Vec2::new(5, 4)));
+        assert_eq!(state.round(), ROUND_LIMIT + 1);
+        assert_eq!(state.outcome().unwrap().reason(), OutcomeReason::Advancement);
+    }
+
+    #[test]
+    fn test_outcome_no_moves() {
+        let state = state_with(Board::new(hashmap![
+            Vec2::new(5, 5) => Piece::new(PieceType::Moewe, Team::Two, 1)
+        ]));
+        assert!(state.is_over());
+        assert_eq!(state.winner(), Some(Team::Two));
+        assert_eq!(state.outcome().unwrap().reason(), OutcomeReason::NoMovesLeft);
+    }
+
+    #[test]
+    fn test_validate_move() {
+        let mut state = state_with(Board::new(hashmap![
+            Vec2::new(0, 3) => Piece::new(PieceType::Herzmuschel, Team::One, 1),
+            Vec2::new(1, 4) => Piece::new(PieceType::Seestern, Team::One, 1),
+            Vec2::new(5, 5) => Piece::new(PieceType::Moewe, Team::Two, 1)
+        ]));
+        let mv = |fx, fy, tx, ty| Move::new(Vec2::new(fx, fy), Vec2::new(tx, ty));
+
+        assert_eq!(state.validate_move(mv(0, 3, 1, 2)), Ok(()));
+        assert_eq!(state.validate_move(mv(2, 2, 3, 3)), Err(InvalidMove::NoPieceAtOrigin(Vec2::new(2, 2))));
+        assert_eq!(state.validate_move(mv(5, 5, 5, 4)), Err(InvalidMove::WrongTeam { expected: Team::One, actual: Team::Two }));
+        assert_eq!(state.validate_move(mv(0, 3, -1, 4)), Err(InvalidMove::OutOfBounds(Vec2::new(-1, 4))));
+        assert_eq!(state.validate_move(mv(0, 3, 1, 3)), Err(InvalidMove::DeltaNotAllowed { piece_type: PieceType::Herzmuschel, delta: Vec2::new(1, 0) }));
+        assert_eq!(state.validate_move(mv(0, 3, 1, 4)), Err(InvalidMove::OwnPieceAtDestination(Vec2::new(1, 4))));
+
+        assert!(state.try_perform(mv(0, 3, 1, 3)).is_err());
+        assert_eq!(state.turn(), 0);
+        assert!(state.try_perform(mv(0, 3, 1, 2)).is_ok());
+        assert_eq!(state.turn(), 1);
+
+        state.ambers.insert(Team::One, 2);
+        state.turn = 2;
+        assert_eq!(state.validate_move(mv(1, 4, 2, 4)), Err(InvalidMove::GameOver));
+    }
+
+    #[test]
+    fn test_unmake() {
+        let with_ambers = state_with(Board::new(hashmap![
+            Vec2::new(1, 1) => Piece::new(PieceType::Robbe, Team::One, 2),
+            Vec2::new(6, 2) => Piece::new(PieceType::Seestern, Team::One, 1),
+            Vec2::new(3, 2) => Piece::new(PieceType::Moewe, Team::Two, 1),
+            Vec2::new(7, 3) => Piece::new(PieceType::Herzmuschel, Team::Two, 2)
+        ]));
+        // The server may omit the ambers of a team
+        let without_ambers = State::new(with_ambers.board().clone(), HashMap::new(), 0, None, Some(Team::One));
+        for original in [with_ambers, without_ambers] {
+            let mut state = original.clone();
+            for m in original.possible_moves() {
+                let undo = state.perform(m);
+                let child = state.clone();
+                for n in child.possible_moves() {
+                    let undo = state.perform(n);
+                    assert_eq!(state, child.child(n));
+                    assert_eq!(state.zobrist(), state.compute_hash());
+                    state.unmake(undo);
+                    assert_eq!(state, child);
+                }
+                state.unmake(undo);
+                assert_eq!(state, original);
+            }
+        }
+    }
+
+    #[test]
+    fn test_zobrist_transposition() {
+        let state = state_with(Board::new(hashmap![
+            Vec2::new(1, 1) => Piece::new(PieceType::Moewe, Team::One, 1),
+            Vec2::new(1, 5) => Piece::new(PieceType::Moewe, Team::One, 1),
+            Vec2::new(6, 2) => Piece::new(PieceType::Moewe, Team::Two, 1),
+            Vec2::new(6, 6) => Piece::new(PieceType::Moewe, Team::Two, 1)
+        ]));
+        let mv = |fx, fy, tx, ty| Move::new(Vec2::new(fx, fy), Vec2::new(tx, ty));
+        let a = state.child(mv(1, 1, 2, 1)).child(mv(6, 2, 5, 2)).child(mv(1, 5, 2, 5));
+        let b = state.child(mv(1, 5, 2, 5)).child(mv(6, 2, 5, 2)).child(mv(1, 1, 2, 1));
+        let c = state.child(mv(1, 5, 2, 5)).child(mv(6, 6, 5, 6)).child(mv(1, 1, 2, 1));
+        assert_eq!(a.zobrist(), b.zobrist());
+        assert_ne!(a.zobrist(), c.zobrist());
+        assert_ne!(state.zobrist(), state.child(mv(1, 1, 2, 1)).zobrist());
+    }
+
+    #[test]
+    fn test_initial() {
+        let state = State::initial(7);
+        assert_eq!(state.board(), &Board::initial(7));
+        assert_eq!(state.current_team(), Some(Team::One));
+        assert_eq!(state.ambers_of(Team::Two), 0);
+        assert!(!state.is_over());
+        assert!(state.moves().all(|m| state.board()[m.from()].team() == Team::One));
+    }
+
+    #[test]
+    fn test_notation() {
+        let state = state_with(Board::new(hashmap![
+            Vec2::new(0, 0) => Piece::new(PieceType::Herzmuschel, Team::One, 1),
+            Vec2::new(3, 0) => Piece::new(PieceType::Robbe, Team::Two, 2),
+            Vec2::new(7, 7) => Piece::new(PieceType::Moewe, Team::Two, 1)
+        ]));
+        let notation = "H2r[2]4/8/8/8/8/8/8/7m 0:0 0 1 -";
+        assert_eq!(state.to_string(), notation);
+        assert_eq!(State::from_str(notation).unwrap(), state);
+
+        assert!(State::from_str("8/8/8/8/8/8/8 0:0 0 1 -").is_err());
+        assert!(State::from_str("9/8/8/8/8/8/8/8 0:0 0 1 -").is_err());
+        assert!(State::from_str("08/8/8/8/8/8/8/8 0:0 0 1 -").is_err());
+        assert!(State::from_str("R07/8/8/8/8/8/8/8 0:0 0 1 -").is_err());
+        assert!(State::from_str("x7/8/8/8/8/8/8/8 0:0 0 1 -").is_err());
+        assert!(State::from_str("8/8/8/8/8/8/8/8 0:0 0 1").is_err());
+        assert!(State::from_str("R[0]7/8/8/8/8/8/8/8 0:0 0 1 -").is_err());
+        assert!(State::from_str("R[300]7/8/8/8/8/8/8/8 0:0 0 1 -").is_err());
+        assert!(State::from_str("R[200]r[200]6/8/8/8/8/8/8/8 0:0 0 1 -").is_err());
+    }
+
+    #[test]
+    fn test_notation_round_trip() {
+        let mut state = State::initial(5);
+        for i in 0..12 {
+            let notation = state.to_string();
+            let parsed = State::from_str(&notation).unwrap();
+            assert_eq!(parsed, state);
+            assert_eq!(parsed.zobrist(), state.zobrist());
+            let moves = state.possible_moves();
+            state.perform(moves[(i * 7) % moves.len()]);
+        }
+    }
+
+    #[test]
+    fn test_capture_moves() {
+        let state = State::from_str("H7/8/8/3Mmm[2]2/8/8/M7/7h 0:0 0 1 -").unwrap();
+        let mv = |fx, fy, tx, ty| Move::new(Vec2::new(fx, fy), Vec2::new(tx, ty));
+        assert_eq!(state.capture_moves().collect::<Vec<_>>(), vec![mv(3, 3, 4, 3)]);
+
+        let mut state = State::from_str("H7/8/8/3Mmm[2]2/8/1m6/M7/7h 0:0 1 1 -").unwrap();
+        assert_eq!(state.capture_moves().collect::<Vec<_>>(), vec![mv(4, 3, 3, 3), mv(1, 5, 0, 5)]);
+        state.perform(mv(1, 5, 0, 5));
+        assert_eq!(state.capture_moves().count(), 1);
     }
 }
@@ -0,0 +1,69 @@
+use super::{Move, Piece, Team};
+
+/// The information needed to revert a move on a board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoardUndo {
+    /// The performed move.
+    pub(super) m: Move,
+    /// The moved piece as it was before the move.
+    pub(super) piece: Piece,
+    /// The piece previously at the destination, if any.
+    pub(super) captured: Option<Piece>,
+}
+
+impl BoardUndo {
+    /// The performed move.
+    #[inline]
+    pub fn performed_move(self) -> Move { self.m }
+
+    /// The moved piece as it was before the move.
+    #[inline]
+    pub fn piece(self) -> Piece { self.piece }
+
+    /// The piece previously at the destination, if any.
+    #[inline]
+    pub fn captured(self) -> Option<Piece> { self.captured }
+}
+
+/// The information needed to revert a move on a state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Undo {
+    /// The board-level undo information.
+    pub(super) board: BoardUndo,
+    /// The team that performed the move.
+    pub(super) team: Team,
+    /// The number of ambers the move earned.
+    pub(super) ambers: usize,
+    /// Whether the team had an entry in the state's ambers before the move.
+    pub(super) had_ambers: bool,
+    /// The most recent move before this one.
+    pub(super) last_move: Option<Move>,
+    /// The Zobrist hash before the move.
+    pub(super) hash: u64,
+}
+
+impl Undo {
+    /// The board-level undo information.
+    #[inline]
+    pub fn board(self) -> BoardUndo { self.board }
+
+    /// The performed move.
+    #[inline]
+    pub fn performed_move(self) -> Move { self.board.m }
+
+    /// The piece previously at the destination, if any.
+    #[inline]
+    pub fn captured(self) -> Option<Piece> { self.board.captured }
+
+    /// The team that performed the move.
+    #[inline]
+    pub fn team(self) -> Team { self.team }
+
+    /// The number of ambers the move earned.
+    #[inline]
+    pub fn ambers(self) -> usize { self.ambers }
+
+    /// The most recent move before this one.
+    #[inline]
+    pub fn last_move(self) -> Option<Move> { self.last_move }
+}
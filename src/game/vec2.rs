@@ -49,6 +49,23 @@ impl Vec2 {
     /// The length of this vector.
     #[inline]
     pub fn length(self) -> f32 { (self.squared_length() as f32).sqrt() }
+
+    /// The position in compact notation, i.e. a letter for the
+    /// x-coordinate followed by the 1-based y-coordinate (e.g. `c5`).
+    /// Panics if the x-coordinate has no letter, i.e. is outside `0..26`.
+    pub fn notation(self) -> String {
+        assert!((0..26).contains(&self.x), "Position {} has no notation!", self);
+        format!("{}{}", (b'a' + self.x as u8) as char, self.y + 1)
+    }
+
+    /// Parses a position in compact notation (e.g. `c5`).
+    pub fn from_notation(s: &str) -> SCResult<Self> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.as_str().parse::<i32>()) {
+            (Some(c @ 'a'..='z'), Ok(y)) => Ok(Self::new(c as i32 - 'a' as i32, y - 1)),
+            _ => Err(SCError::InvalidNotation(format!("Invalid position {}", s))),
+        }
+    }
 }
 
 impl Add for Vec2 {
@@ -131,4 +148,11 @@ mod tests {
             <coords x="23" y="0" />
         "#).unwrap()).unwrap(), Vec2::new(23, 0));
     }
+
+    #[test]
+    fn test_notation() {
+        assert_eq!(Vec2::new(2, 4).notation(), "c5");
+        assert_eq!(Vec2::from_notation("h1").unwrap(), Vec2::new(7, 0));
+        assert!(Vec2::from_notation("5c").is_err());
+    }
 }
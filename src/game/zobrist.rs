@@ -0,0 +1,70 @@
+use super::{Board, Piece, Team, Vec2, FIELD_COUNT, ROUND_LIMIT};
+
+/// The maximum tower height distinguished by the keys. Taller
+/// towers never remain on the board, since they turn into ambers.
+const MAX_TOWER: usize = 4;
+/// The maximum number of ambers distinguished by the keys.
+const MAX_AMBERS: usize = 8;
+/// The number of turns distinguished by the keys, i.e. all
+/// turns up to the one where the round limit ends the game.
+const TURNS: usize = 2 * ROUND_LIMIT + 1;
+
+const PIECE_KEYS: usize = 2 * 4 * MAX_TOWER * FIELD_COUNT;
+const AMBER_KEYS: usize = 2 * (MAX_AMBERS + 1);
+const KEY_COUNT: usize = PIECE_KEYS + AMBER_KEYS + 2 + TURNS;
+
+/// Pseudo-random keys, generated at compile time using SplitMix64.
+static KEYS: [u64; KEY_COUNT] = generate_keys();
+
+const fn generate_keys() -> [u64; KEY_COUNT] {
+    let mut keys = [0; KEY_COUNT];
+    let mut seed: u64 = 0x5EE5_7E25_C4A0_2022;
+    let mut i = 0;
+    while i < KEY_COUNT {
+        seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+/// The key for the given piece at the given position.
+#[inline]
+pub fn piece_key(pos: Vec2, piece: Piece) -> u64 {
+    let team = piece.team().index() as usize;
+    let tower = piece.count().clamp(1, MAX_TOWER) - 1;
+    KEYS[((team * 4 + piece.piece_type().index()) * MAX_TOWER + tower) * FIELD_COUNT + Board::field_index(pos)]
+}
+
+/// The key for the given team owning the given number of ambers.
+#[inline]
+pub fn ambers_key(team: Team, ambers: usize) -> u64 {
+    KEYS[PIECE_KEYS + team.index() as usize * (MAX_AMBERS + 1) + ambers.min(MAX_AMBERS)]
+}
+
+/// The key toggled on every turn, i.e. for the side to move.
+#[inline]
+pub fn side_key() -> u64 {
+    KEYS[PIECE_KEYS + AMBER_KEYS]
+}
+
+/// The key for states where the second team starts.
+#[inline]
+pub fn second_team_starts_key() -> u64 {
+    KEYS[PIECE_KEYS + AMBER_KEYS + 1]
+}
+
+/// The key for the given turn. It is not part of the state's hash,
+/// but can be mixed in where the remaining rounds matter.
+#[inline]
+pub fn turn_key(turn: usize) -> u64 {
+    KEYS[PIECE_KEYS + AMBER_KEYS + 2 + turn.min(TURNS - 1)]
+}
+
+/// Computes the key of all pieces on the board.
+pub fn board_key(board: &Board) -> u64 {
+    board.iter_pieces().fold(0, |key, (pos, piece)| key ^ piece_key(pos, piece))
+}
@@ -2,4 +2,6 @@ pub mod client;
 pub mod logic;
 pub mod protocol;
 pub mod game;
+pub mod eval;
+pub mod search;
 pub mod util;
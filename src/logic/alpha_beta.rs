@@ -0,0 +1,148 @@
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc}, thread::{self, JoinHandle}};
+
+use log::{debug, info};
+
+use crate::{client::SCClientDelegate, eval::{Evaluator, WeightedEvaluator}, game::{Move, State, Team}, search::{AlphaBeta, ParallelSearch, SearchResult}, util::TimeManager};
+
+/// A search running on a background thread during the opponent's turn.
+struct Ponder {
+    stop_flag: Arc<AtomicBool>,
+    handle: JoinHandle<SearchResult>,
+}
+
+/// A game logic that picks moves using an alpha-beta search,
+/// optionally on multiple threads. While pondering, the position after
+/// the opponent's expected reply is searched on a background thread
+/// sharing the transposition table. If the opponent plays that reply,
+/// the search of our next move continues with the pondered entries.
+pub struct AlphaBetaLogic<E> {
+    search: ParallelSearch<E>,
+    ponder: Option<Ponder>,
+    /// The opponent's reply expected by the last search.
+    expected_reply: Option<Move>,
+}
+
+impl<E> AlphaBetaLogic<E> {
+    /// Stops the ponder search, if any, and waits for its result.
+    fn finish_pondering(&mut self) -> Option<SearchResult> {
+        let ponder = self.ponder.take()?;
+        ponder.stop_flag.store(true, Ordering::Relaxed);
+        ponder.handle.join().ok()
+    }
+}
+
+impl<E> AlphaBetaLogic<E> where E: Evaluator {
+    /// Creates a new game logic using the given search.
+    pub fn new(search: AlphaBeta<E>) -> Self {
+        Self { search: ParallelSearch::new(search), ponder: None, expected_reply: None }
+    }
+
+    /// Sets the number of threads used for searching.
+    pub fn threads(mut self, threads: usize) -> Self where E: Clone {
+        self.search.set_threads(threads);
+        self
+    }
+}
+
+impl Default for AlphaBetaLogic<WeightedEvaluator> {
+    fn default() -> Self {
+        Self::new(AlphaBeta::new(WeightedEvaluator::default()))
+    }
+}
+
+impl<E> AlphaBetaLogic<E> where E: Evaluator + Send {
+    /// Searches the state, continuing within the generation of the
+    /// table if the opponent played the reply we pondered on.
+    fn search(&mut self, state: &State, time: &TimeManager) -> SearchResult {
+        let pondered = self.finish_pondering().is_some();
+        let result = if pondered && self.expected_reply.is_some_and(|m| state.last_move() == Some(m)) {
+            info!("Opponent played the expected reply, continuing with the pondered table entries");
+            self.search.continue_with(state, time)
+        } else {
+            self.search.search_with(state, time)
+        };
+        self.expected_reply = result.pv().get(1).copied();
+        result
+    }
+}
+
+impl<E> Drop for AlphaBetaLogic<E> {
+    fn drop(&mut self) {
+        self.finish_pondering();
+    }
+}
+
+impl<E> SCClientDelegate for AlphaBetaLogic<E> where E: Evaluator + Clone + Send + 'static {
+    fn start_pondering(&mut self, state: &State) {
+        self.finish_pondering();
+        let Some(reply) = self.expected_reply.filter(|&m| state.validate_move(m).is_ok()) else {
+            debug!("Not pondering without an expected reply");
+            return;
+        };
+        // The ponder search stays within the generation of our last
+        // search, whose entries it extends
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let mut search = self.search.main().helper(0, stop_flag.clone());
+        let state = state.child(reply);
+        let handle = thread::spawn(move || search.search(&state));
+        debug!("Started pondering on the expected reply {}", reply);
+        self.ponder = Some(Ponder { stop_flag, handle });
+    }
+
+    fn stop_pondering(&mut self) {
+        if let Some(result) = self.finish_pondering() {
+            debug!("Stopped pondering at depth {} after {} nodes", result.depth(), result.nodes());
+        }
+    }
+
+    fn request_move(&mut self, state: &State, _my_team: Team, time: &TimeManager) -> Move {
+        info!("Requested move");
+        let result = self.search(state, time);
+        let chosen_move = result.best_move()
+            .or_else(|| state.moves().next())
+            .expect("No move found!");
+        info!("Chose move {} (score: {}, depth: {}, nodes: {}, took {} ms)", chosen_move, result.score(), result.depth(), result.nodes(), time.elapsed().as_millis());
+        chosen_move
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::{client::SCClientDelegate, eval::SimpleEvaluator, game::State, logic::AlphaBetaLogic, search::AlphaBeta, util::TimeManager};
+
+    /// Ponders on the state until the ponder search completes.
+    fn ponder(logic: &mut AlphaBetaLogic<SimpleEvaluator>, state: &State) {
+        logic.start_pondering(state);
+        while !logic.ponder.as_ref().unwrap().handle.is_finished() {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_pondering() {
+        let mut logic = AlphaBetaLogic::new(AlphaBeta::new(SimpleEvaluator).max_depth(4));
+        let time = TimeManager::with_limit(Duration::from_secs(10));
+        let mut state = State::initial(8);
+        let first = logic.search(&state, &time);
+        state.perform(first.best_move().unwrap());
+
+        // A ponder hit continues with the pondered entries
+        ponder(&mut logic, &state);
+        let generation = logic.search.table().generation();
+        state.perform(logic.expected_reply.unwrap());
+        let hit = logic.search(&state, &time);
+        assert_eq!(logic.search.table().generation(), generation);
+        assert!(hit.nodes() < AlphaBeta::new(SimpleEvaluator).max_depth(4).search(&state).nodes());
+
+        // A ponder miss starts a new generation
+        state.perform(hit.best_move().unwrap());
+        ponder(&mut logic, &state);
+        let expected = logic.expected_reply.unwrap();
+        let other = state.moves().find(|&m| m != expected).unwrap();
+        state.perform(other);
+        logic.search(&state, &time);
+        assert_eq!(logic.search.table().generation(), generation.wrapping_add(1));
+    }
+}
@@ -0,0 +1,53 @@
+use log::info;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{client::SCClientDelegate, game::{Move, State, Team}, protocol::GameResult, search::OpeningBook, util::TimeManager};
+
+/// A game logic that plays moves from an opening book while
+/// possible and asks the wrapped logic otherwise.
+pub struct BookLogic<D> {
+    book: OpeningBook,
+    delegate: D,
+    rng: StdRng,
+}
+
+impl<D> BookLogic<D> where D: SCClientDelegate {
+    /// Creates a new game logic consulting the given book before the
+    /// delegate, seeding the random number generator used to pick
+    /// between book moves.
+    pub fn new(book: OpeningBook, delegate: D, seed: u64) -> Self {
+        Self { book, delegate, rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl<D> SCClientDelegate for BookLogic<D> where D: SCClientDelegate {
+    fn on_update_state(&mut self, state: &State) {
+        self.delegate.on_update_state(state);
+    }
+
+    fn on_game_end(&mut self, result: &GameResult) {
+        self.delegate.on_game_end(result);
+    }
+
+    fn on_welcome(&mut self, team: Team) {
+        self.delegate.on_welcome(team);
+    }
+
+    fn start_pondering(&mut self, state: &State) {
+        self.delegate.start_pondering(state);
+    }
+
+    fn stop_pondering(&mut self) {
+        self.delegate.stop_pondering();
+    }
+
+    fn request_move(&mut self, state: &State, my_team: Team, time: &TimeManager) -> Move {
+        match self.book.choose(state, &mut self.rng) {
+            Some(m) => {
+                info!("Chose book move {}", m);
+                m
+            },
+            None => self.delegate.request_move(state, my_team, time),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use log::info;
+
+use crate::{client::SCClientDelegate, game::{Move, State, Team}, protocol::GameResult, search::{EndgameSolver, EndgameValue}, util::TimeManager};
+
+/// A game logic that plays solved moves in positions with few pieces
+/// and asks the wrapped logic otherwise. Lost positions are left to the
+/// wrapped logic too, since the opponent may still make a mistake.
+pub struct EndgameLogic<D> {
+    solver: EndgameSolver,
+    delegate: D,
+}
+
+impl<D> EndgameLogic<D> where D: SCClientDelegate {
+    /// Creates a new game logic consulting the given solver before the delegate.
+    pub fn new(solver: EndgameSolver, delegate: D) -> Self {
+        Self { solver, delegate }
+    }
+}
+
+impl<D> SCClientDelegate for EndgameLogic<D> where D: SCClientDelegate {
+    fn on_update_state(&mut self, state: &State) {
+        self.delegate.on_update_state(state);
+    }
+
+    fn on_game_end(&mut self, result: &GameResult) {
+        self.delegate.on_game_end(result);
+    }
+
+    fn on_welcome(&mut self, team: Team) {
+        self.delegate.on_welcome(team);
+    }
+
+    fn start_pondering(&mut self, state: &State) {
+        self.delegate.start_pondering(state);
+    }
+
+    fn stop_pondering(&mut self) {
+        self.delegate.stop_pondering();
+    }
+
+    fn request_move(&mut self, state: &State, my_team: Team, time: &TimeManager) -> Move {
+        // Leave the wrapped logic at least half of the time if solving fails
+        let solver_time = time.limited(Some(time.allotted() / 2));
+        if let Some(solution) = self.solver.solve_with(state, &solver_time) {
+            if let Some(m) = solution.best_move().filter(|_| solution.value() != EndgameValue::Loss) {
+                info!("Chose solved move {} ({:?}, {} nodes)", m, solution.value(), solution.nodes());
+                return m;
+            }
+        }
+        self.delegate.request_move(state, my_team, time)
+    }
+}
@@ -0,0 +1,38 @@
+use log::info;
+
+use crate::{client::SCClientDelegate, eval::{Evaluator, WeightedEvaluator}, game::{Move, State, Team}, search::terminal_score, util::TimeManager};
+
+/// A game logic that picks the move leading to the best
+/// evaluated position, without looking further ahead.
+pub struct GreedyLogic<E> {
+    evaluator: E,
+}
+
+impl<E> GreedyLogic<E> where E: Evaluator {
+    /// Creates a new game logic using the given evaluator.
+    pub fn new(evaluator: E) -> Self {
+        Self { evaluator }
+    }
+}
+
+impl Default for GreedyLogic<WeightedEvaluator> {
+    fn default() -> Self {
+        Self::new(WeightedEvaluator::default())
+    }
+}
+
+impl<E> SCClientDelegate for GreedyLogic<E> where E: Evaluator {
+    fn request_move(&mut self, state: &State, my_team: Team, _time: &TimeManager) -> Move {
+        info!("Requested move");
+        let (chosen_move, score) = state.moves()
+            .map(|m| {
+                let child = state.child(m);
+                let score = terminal_score(&child, my_team, 1).unwrap_or_else(|| self.evaluator.evaluate(&child, my_team));
+                (m, score)
+            })
+            .reduce(|best, candidate| if candidate.1 > best.1 { candidate } else { best })
+            .expect("No move found!");
+        info!("Chose move {} (score: {})", chosen_move, score);
+        chosen_move
+    }
+}
@@ -0,0 +1,27 @@
+use log::info;
+
+use crate::{client::SCClientDelegate, game::{Move, State, Team}, search::{Mcts, RolloutPolicy}, util::TimeManager};
+
+/// A game logic that picks moves using a Monte Carlo tree search.
+pub struct MctsLogic<P> {
+    search: Mcts<P>,
+}
+
+impl<P> MctsLogic<P> where P: RolloutPolicy {
+    /// Creates a new game logic using the given search.
+    pub fn new(search: Mcts<P>) -> Self {
+        Self { search }
+    }
+}
+
+impl<P> SCClientDelegate for MctsLogic<P> where P: RolloutPolicy {
+    fn request_move(&mut self, state: &State, _my_team: Team, time: &TimeManager) -> Move {
+        info!("Requested move");
+        let result = self.search.search_with(state, time);
+        let chosen_move = result.best_move()
+            .or_else(|| state.moves().next())
+            .expect("No move found!");
+        info!("Chose move {} (win rate: {:.3}, iterations: {}, reused visits: {}, took {} ms)", chosen_move, result.win_rate(), result.iterations(), result.reused_visits(), time.elapsed().as_millis());
+        chosen_move
+    }
+}
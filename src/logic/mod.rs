@@ -0,0 +1,15 @@
+mod random;
+mod greedy;
+mod alpha_beta;
+mod mcts;
+mod book;
+mod endgame;
+mod registry;
+
+pub use random::*;
+pub use greedy::*;
+pub use alpha_beta::*;
+pub use mcts::*;
+pub use book::*;
+pub use endgame::*;
+pub use registry::*;
@@ -0,0 +1,50 @@
+use log::info;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{client::SCClientDelegate, game::{Move, Team, State}, util::TimeManager};
+
+/// An empty game logic structure that
+/// implements the client delegate trait
+/// and thus is responsible e.g. for picking
+/// a move when requested. Moves are picked
+/// at random, reproducibly from the seed.
+pub struct OwnGameLogic {
+    rng: StdRng,
+}
+
+impl OwnGameLogic {
+    /// Creates a new game logic seeding its random number generator.
+    pub fn new(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl SCClientDelegate for OwnGameLogic {
+    fn request_move(&mut self, state: &State, _my_team: Team, _time: &TimeManager) -> Move {
+        info!("Requested move");
+        let chosen_move = *state.possible_moves()
+            .choose(&mut self.rng)
+            .expect("No move found!");
+        info!("Chose move {}", chosen_move);
+        chosen_move
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{client::SCClientDelegate, game::{GameRecord, State, Team}, logic::OwnGameLogic, util::TimeManager};
+
+    fn play(seed: u64) -> GameRecord {
+        let mut logic = OwnGameLogic::new(seed);
+        let time = TimeManager::with_limit(Duration::from_secs(1));
+        GameRecord::play(State::initial(0), |state| logic.request_move(state, state.current_team().unwrap_or(Team::One), &time))
+    }
+
+    #[test]
+    fn test_reproducible() {
+        assert_eq!(play(42), play(42));
+        assert_ne!(play(42).moves(), play(43).moves());
+    }
+}
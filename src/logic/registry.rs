@@ -0,0 +1,222 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
+
+use crate::{client::SCClientDelegate, eval::{WeightedEvaluator, Weights}, search::{AlphaBeta, CaptureRollout, EndgameSolver, Mcts, TranspositionTable, DEFAULT_TABLE_SIZE_MB}, util::{SCError, SCResult}};
+
+use super::{AlphaBetaLogic, EndgameLogic, GreedyLogic, MctsLogic, OwnGameLogic};
+
+/// Parameters of a strategy as `key=value` pairs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StrategyParams {
+    values: HashMap<String, String>,
+    /// Already loaded weights, which take precedence
+    /// over the `weights` parameter.
+    weights: Option<Weights>,
+}
+
+impl StrategyParams {
+    /// Creates empty parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the given parameter.
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.values.insert(key.to_owned(), value.to_owned());
+    }
+
+    /// Returns the parameters with the given parameter set.
+    pub fn with(mut self, key: &str, value: &str) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    /// Sets a parameter from a `key=value` pair.
+    pub fn set_pair(&mut self, pair: &str) -> SCResult<()> {
+        let (key, value) = pair.split_once('=')
+            .ok_or_else(|| SCError::InvalidNotation(format!("Expected 'key=value', got '{}'", pair)))?;
+        self.set(key.trim(), value.trim());
+        Ok(())
+    }
+
+    /// The raw value of the given parameter.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|v| v.as_str())
+    }
+
+    /// Parses the given parameter, if present.
+    pub fn parse<T>(&self, key: &str) -> SCResult<Option<T>> where T: FromStr {
+        self.get(key)
+            .map(|v| v.parse().map_err(|_| SCError::Custom(format!("Invalid value '{}' for parameter {}", v, key))))
+            .transpose()
+    }
+
+    /// Parses the given parameter or falls back to the default.
+    pub fn parse_or<T>(&self, key: &str, default: T) -> SCResult<T> where T: FromStr {
+        Ok(self.parse(key)?.unwrap_or(default))
+    }
+
+    /// Parses a duration in milliseconds, if present.
+    pub fn millis(&self, key: &str) -> SCResult<Option<Duration>> {
+        Ok(self.parse(key)?.map(Duration::from_millis))
+    }
+
+    /// Parses the `seed` parameter for random number generators,
+    /// which is required to make games reproducible.
+    pub fn seed(&self) -> SCResult<u64> {
+        self.parse("seed")?.ok_or_else(|| SCError::Custom("Missing parameter seed".to_owned()))
+    }
+
+    /// Sets already loaded evaluation weights.
+    pub fn set_weights(&mut self, weights: Weights) {
+        self.weights = Some(weights);
+    }
+
+    /// The evaluation weights that were set, otherwise loads them from
+    /// the file given by the `weights` parameter or uses the defaults.
+    pub fn weights(&self) -> SCResult<Weights> {
+        match self.weights {
+            Some(weights) => Ok(weights),
+            None => self.get("weights").map_or(Ok(Weights::default()), Weights::load),
+        }
+    }
+}
+
+/// Creates a strategy from its parameters.
+pub type StrategyFactory = Box<dyn Fn(&StrategyParams) -> SCResult<Box<dyn SCClientDelegate>>>;
+
+/// A named strategy in a registry.
+struct Strategy {
+    name: String,
+    description: String,
+    factory: StrategyFactory,
+}
+
+/// A collection of strategies that can be selected by name,
+/// e.g. from the command line.
+pub struct StrategyRegistry {
+    strategies: Vec<Strategy>,
+}
+
+impl StrategyRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self { strategies: Vec::new() }
+    }
+
+    /// Registers a strategy, replacing any strategy with the same name.
+    pub fn register(&mut self, name: &str, description: &str, factory: impl Fn(&StrategyParams) -> SCResult<Box<dyn SCClientDelegate>> + 'static) {
+        self.strategies.retain(|s| s.name != name);
+        self.strategies.push(Strategy { name: name.to_owned(), description: description.to_owned(), factory: Box::new(factory) });
+    }
+
+    /// The names and descriptions of the registered strategies.
+    pub fn strategies(&self) -> impl Iterator<Item=(&str, &str)> {
+        self.strategies.iter().map(|s| (s.name.as_str(), s.description.as_str()))
+    }
+
+    /// Creates the strategy with the given name.
+    pub fn create(&self, name: &str, params: &StrategyParams) -> SCResult<Box<dyn SCClientDelegate>> {
+        let strategy = self.strategies.iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| SCError::UnknownVariant(format!("Unknown strategy {}", name)))?;
+        (strategy.factory)(params)
+    }
+}
+
+/// Wraps the delegate in an endgame solver if the
+/// `endgame` parameter (the maximum piece count) is set.
+fn with_endgame(params: &StrategyParams, delegate: Box<dyn SCClientDelegate>) -> SCResult<Box<dyn SCClientDelegate>> {
+    Ok(match params.parse::<usize>("endgame")? {
+        Some(max_pieces) => Box::new(EndgameLogic::new(EndgameSolver::new().max_pieces(max_pieces), delegate)),
+        None => delegate,
+    })
+}
+
+/// Registers the built-in strategies.
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register("random", "Picks random moves (seed)", |params| Ok(Box::new(OwnGameLogic::new(params.seed()?))));
+        registry.register("greedy", "Picks the best evaluated move (weights)", |params| {
+            Ok(Box::new(GreedyLogic::new(WeightedEvaluator::new(params.weights()?))))
+        });
+        registry.register("alpha-beta", "Alpha-beta search (depth, time, threads, table, quiescence, endgame, weights)", |params| {
+            let table = Arc::new(TranspositionTable::new(params.parse_or("table", DEFAULT_TABLE_SIZE_MB)?));
+            let search = AlphaBeta::with_table(WeightedEvaluator::new(params.weights()?), table)
+                .max_depth(params.parse_or("depth", usize::MAX)?)
+                .time_limit(params.millis("time")?)
+                .quiescence(params.parse_or("quiescence", true)?);
+            let logic = AlphaBetaLogic::new(search).threads(params.parse_or("threads", 1)?);
+            with_endgame(params, Box::new(logic))
+        });
+        registry.register("mcts", "Monte Carlo tree search (iterations, time, exploration, endgame, seed)", |params| {
+            let mut search = Mcts::new(CaptureRollout::default(), params.seed()?)
+                .iterations(params.parse("iterations")?)
+                .time_limit(params.millis("time")?);
+            if let Some(exploration) = params.parse("exploration")? {
+                search = search.exploration(exploration);
+            }
+            with_endgame(params, Box::new(MctsLogic::new(search)))
+        });
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{eval::{Term, Weights}, game::{GameRecord, State, Team}, logic::{StrategyParams, StrategyRegistry}, util::TimeManager};
+
+    #[test]
+    fn test_params() {
+        let mut params = StrategyParams::new().with("depth", "4");
+        params.set_pair("time = 500").unwrap();
+        assert_eq!(params.parse::<usize>("depth").unwrap(), Some(4));
+        assert_eq!(params.parse_or("threads", 1).unwrap(), 1);
+        assert_eq!(params.millis("time").unwrap().unwrap().as_millis(), 500);
+        assert!(params.with("depth", "deep").parse::<usize>("depth").is_err());
+        assert!(StrategyParams::new().set_pair("depth").is_err());
+
+        let weights = Weights::zero().with(Term::Ambers, 7);
+        let mut params = StrategyParams::new().with("weights", "missing.txt");
+        assert!(params.weights().is_err());
+        params.set_weights(weights);
+        assert_eq!(params.weights().unwrap(), weights);
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = StrategyRegistry::default();
+        let names = registry.strategies().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names, ["random", "greedy", "alpha-beta", "mcts"]);
+        for name in names {
+            assert!(registry.create(name, &StrategyParams::new().with("endgame", "4").with("seed", "1")).is_ok());
+        }
+        assert!(registry.create("random", &StrategyParams::new()).is_err());
+        assert!(registry.create("alpha-beta", &StrategyParams::new().with("depth", "x")).is_err());
+        assert!(registry.create("minimax", &StrategyParams::new()).is_err());
+
+        registry.register("custom", "A custom strategy", |_| Ok(Box::new(crate::logic::OwnGameLogic::new(0))));
+        assert!(registry.create("custom", &StrategyParams::new()).is_ok());
+    }
+
+    #[test]
+    fn test_reproducible() {
+        // Plays a game of the strategy against itself
+        let play = |name: &str, params: &StrategyParams| {
+            let registry = StrategyRegistry::default();
+            let mut logic = registry.create(name, params).unwrap();
+            let time = TimeManager::with_limit(Duration::from_secs(10));
+            GameRecord::play(State::initial(7), |state| logic.request_move(state, state.current_team().unwrap_or(Team::One), &time))
+        };
+        for (name, params) in [
+            ("random", StrategyParams::new()),
+            ("alpha-beta", StrategyParams::new().with("depth", "2")),
+            ("mcts", StrategyParams::new().with("iterations", "100")),
+        ] {
+            let params = params.with("seed", "5");
+            assert_eq!(play(name, &params).moves(), play(name, &params).moves(), "Moves of {}", name);
+        }
+    }
+}
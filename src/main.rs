@@ -1,17 +1,85 @@
 use std::env;
+use std::fs;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 use simplelog::{SimpleLogger, Config};
-use log::LevelFilter;
+use log::{info, warn, LevelFilter};
 use getopts::Options;
 use socha_client_2022::client::{SCClient, DebugMode};
-use socha_client_2022::logic::OwnGameLogic;
+use socha_client_2022::eval::{Tuner, TuningSample, WeightedEvaluator, Weights};
+use socha_client_2022::game::{GameRecord, State, perft_divide};
+use socha_client_2022::logic::{BookLogic, StrategyParams, StrategyRegistry};
+use socha_client_2022::search::{AlphaBeta, OpeningBook, DEFAULT_BOOK_PLIES};
+use socha_client_2022::util::TimeConfig;
 
-fn print_usage(program: &str, options: Options) {
+fn print_usage(program: &str, options: Options, registry: &StrategyRegistry) {
     let brief = format!("Usage: {} [options]", program);
     print!("{}", options.usage(&brief));
+    println!("\nStrategies (with their parameters):");
+    for (name, description) in registry.strategies() {
+        println!("    {:<12} {}", name, description);
+    }
+}
+
+fn run_perft(state: &mut State, depth: usize) {
+    println!("{:#}", state);
+    let start = Instant::now();
+    let mut total = 0;
+    for (m, count) in perft_divide(state, depth) {
+        println!("{}: {}", m.notation(), count);
+        total += count;
+    }
+    let elapsed = start.elapsed();
+    println!("Total: {}", total);
+    println!("Took {:.3} s ({:.0} nodes/s)", elapsed.as_secs_f64(), total as f64 / elapsed.as_secs_f64());
+}
+
+/// The search depth used by both players in self-played games.
+const SELF_PLAY_DEPTH: usize = 3;
+
+/// The maximum number of passes over the weights when tuning.
+const TUNING_PASSES: usize = 200;
+
+fn self_play(weights: Weights, games: u64) -> Vec<GameRecord> {
+    let mut search = AlphaBeta::new(WeightedEvaluator::new(weights)).max_depth(SELF_PLAY_DEPTH);
+    (0..games)
+        .map(|seed| GameRecord::play(State::initial(seed), |state| search.search(state).best_move().expect("No move found!")))
+        .collect()
+}
+
+fn load_records(records: Option<String>, weights: Weights, games: u64) -> Vec<GameRecord> {
+    match records {
+        Some(records) => fs::read_to_string(&records).expect("Could not read game records.")
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| GameRecord::from_str(l).expect("Invalid game record."))
+            .collect(),
+        None => self_play(weights, games),
+    }
+}
+
+fn build_book(path: &str, records: Vec<GameRecord>, plies: usize) {
+    let book = OpeningBook::from_records(&records, plies);
+    book.save(path).expect("Could not write opening book.");
+    println!("Wrote {} positions from {} games to {}", book.len(), records.len(), path);
+}
+
+fn tune(path: &str, records: Vec<GameRecord>, weights: Weights) {
+    let samples = records.iter().flat_map(TuningSample::from_record).collect();
+    let tuner = Tuner::new(samples);
+    println!("Tuning on {} positions from {} games (error: {:.6})", tuner.sample_count(), records.len(), tuner.error(&weights));
+    let tuned = tuner.tune(weights, TUNING_PASSES);
+    println!("{}", tuned);
+    tuned.save(path).expect("Could not write weights.");
+    println!("Wrote weights (error: {:.6}) to {}", tuner.error(&tuned), path);
 }
 
 fn main() {
+    // Register the strategies, custom strategies can be added
+    // here using `registry.register`
+    let registry = StrategyRegistry::default();
+
     // Parse command line arguments
     let args = env::args().collect::<Vec<_>>();
     let mut options = Options::new();
@@ -21,11 +89,27 @@ fn main() {
     options.optopt("l", "level", "Optionally provides a custom log level ('Info' by default)", "LEVEL");
     options.optflag("d", "debug-reader", "Reads incoming XML messages from the console for debugging");
     options.optflag("D", "debug-writer", "Prints incoming XML messages to the console for debugging");
+    options.optopt("", "time-budget", "The time per move in milliseconds, measured from the move request (2000 by default)", "MILLIS");
+    options.optopt("", "time-margin", "The part of the time budget reserved for latency in milliseconds (300 by default)", "MILLIS");
+    options.optopt("s", "strategy", "The strategy used to pick moves ('random' by default)", "NAME");
+    options.optmulti("", "param", "A parameter of the strategy, e.g. 'depth=4' or 'time=1500' (may be repeated)", "KEY=VALUE");
+    options.optopt("", "seed", "The seed for all random decisions, logged at startup to reproduce runs (random by default)", "SEED");
+    options.optflag("", "ponder", "Lets the logic think during the opponent's turn");
+    options.optopt("", "book", "An opening book to play from", "FILE");
+    options.optopt("", "record", "Appends the played game to the given file", "FILE");
+    options.optopt("", "build-book", "Builds an opening book from game records or self-play instead of connecting to a server", "FILE");
+    options.optopt("", "tune", "Tunes the evaluation weights on game records or self-play instead of connecting to a server", "FILE");
+    options.optopt("", "weights", "The evaluation weights as 'name = weight' lines used by the 'greedy' and 'alpha-beta' strategies (also the starting point for tuning)", "FILE");
+    options.optopt("", "records", "The game records (one per line) to build the opening book from or to tune on", "FILE");
+    options.optopt("", "games", "The number of self-played games if no records are given (100 by default)", "COUNT");
+    options.optopt("", "book-plies", &format!("The number of plies per game added to the opening book ({} by default)", DEFAULT_BOOK_PLIES), "COUNT");
+    options.optopt("", "perft", "Counts the leaf nodes of the game tree up to the given depth instead of connecting to a server", "DEPTH");
+    options.optopt("", "position", "The position used by offline modes in FEN-like notation (a start position by default)", "FEN");
     options.optflag("H", "help", "Prints usage info");
     
     let parsed_args = options.parse(&args[1..]).expect("Could not parse arguments!");
     if parsed_args.opt_present("help") {
-        print_usage(&args[0], options);
+        print_usage(&args[0], options, &registry);
         return;
     }
     
@@ -37,12 +121,65 @@ fn main() {
     // Setup logging
     SimpleLogger::init(LevelFilter::from_str(&level).expect("Invalid log level."), Config::default()).expect("Could not initialize logger.");
     
+    let seed: u64 = parsed_args.opt_str("seed").map_or_else(rand::random, |s| s.parse().expect("Invalid seed."));
+    info!("Using seed {} (reproduce with --seed {})", seed, seed);
+    
+    // Run offline modes
+    if let Some(depth) = parsed_args.opt_str("perft") {
+        let depth = depth.parse::<usize>().expect("Invalid perft depth.");
+        let mut state = parsed_args.opt_str("position")
+            .map(|p| State::from_str(&p).expect("Invalid position."))
+            .unwrap_or_else(|| State::initial(0));
+        run_perft(&mut state, depth);
+        return;
+    }
+    let weights = parsed_args.opt_str("weights")
+        .map(|path| Weights::load(path).expect("Could not read weights."))
+        .unwrap_or_default();
+    let games = parsed_args.opt_str("games").map_or(100, |g| g.parse().expect("Invalid game count."));
+    if let Some(path) = parsed_args.opt_str("build-book") {
+        let plies = parsed_args.opt_str("book-plies").map_or(DEFAULT_BOOK_PLIES, |p| p.parse().expect("Invalid ply count."));
+        build_book(&path, load_records(parsed_args.opt_str("records"), weights, games), plies);
+        return;
+    }
+    if let Some(path) = parsed_args.opt_str("tune") {
+        tune(&path, load_records(parsed_args.opt_str("records"), weights, games), weights);
+        return;
+    }
+    
     // Setup the client and the delegate
     let debug_mode = DebugMode {
         debug_reader: parsed_args.opt_present("debug-reader"),
         debug_writer: parsed_args.opt_present("debug-writer")
     };
-    let client = SCClient::new(OwnGameLogic, debug_mode, reservation);
+    let mut time_config = TimeConfig::default();
+    if let Some(budget) = parsed_args.opt_str("time-budget") {
+        time_config.budget = Duration::from_millis(budget.parse().expect("Invalid time budget."));
+    }
+    if let Some(margin) = parsed_args.opt_str("time-margin") {
+        time_config.safety_margin = Duration::from_millis(margin.parse().expect("Invalid time margin."));
+    }
+    let book = parsed_args.opt_str("book")
+        .map(|path| OpeningBook::load(path).expect("Could not read opening book."))
+        .unwrap_or_default();
+    let strategy = parsed_args.opt_str("strategy").unwrap_or("random".to_owned());
+    let mut params = StrategyParams::new();
+    for pair in parsed_args.opt_strs("param") {
+        params.set_pair(&pair).expect("Invalid strategy parameter.");
+    }
+    if let (Some(path), None) = (parsed_args.opt_str("weights"), params.get("weights")) {
+        info!("Using evaluation weights from {}", path);
+        params.set_weights(weights);
+    }
+    if params.get("seed").is_some_and(|s| s != seed.to_string()) {
+        warn!("Ignoring the seed parameter in favor of --seed {}", seed);
+    }
+    params.set("seed", &seed.to_string());
+    let delegate = registry.create(&strategy, &params).expect("Could not create strategy.");
+    let client = SCClient::new(BookLogic::new(book, delegate, seed), debug_mode, reservation)
+        .time_config(time_config)
+        .ponder(parsed_args.opt_present("ponder"))
+        .record_path(parsed_args.opt_str("record").map(PathBuf::from));
     
     let _result = client.connect(&host, port).expect("Error while running client.");
 }
@@ -10,7 +10,7 @@ pub enum EventPayload {
     /// A welcome message by the server.
     Welcome(Team),
     /// A game state.
-    Memento(State),
+    Memento(Box<State>),
     /// A request by the server to perform a move.
     MoveRequest,
     /// A game result.
@@ -38,7 +38,7 @@ impl TryFrom<&Element> for EventPayload {
     fn try_from(elem: &Element) -> SCResult<Self> {
         match elem.attribute("class")? {
             "welcomeMessage" => Ok(Self::Welcome(elem.attribute("color")?.parse()?)),
-            "memento" => Ok(Self::Memento(elem.child_by_name("state")?.try_into()?)),
+            "memento" => Ok(Self::Memento(Box::new(elem.child_by_name("state")?.try_into()?))),
             "moveRequest" => Ok(Self::MoveRequest),
             "result" => Ok(Self::GameResult(elem.try_into()?)),
             "error" => Err(SCError::ServerError(elem.attribute("message")?.to_owned())),
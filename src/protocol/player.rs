@@ -13,7 +13,7 @@ impl Player {
     }
 
     #[inline]
-    pub fn name(&self) -> Option<&str> { self.name.as_ref().map(|n| n.as_str()) }
+    pub fn name(&self) -> Option<&str> { self.name.as_deref() }
 
     #[inline]
     pub fn team(&self) -> Team { self.team }
@@ -0,0 +1,457 @@
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc}, time::Duration};
+
+use log::debug;
+
+use crate::{eval::Evaluator, game::{zobrist, Move, State}, util::TimeManager};
+
+use super::{is_win_score, score_from_table, score_to_table, terminal_score, Bound, MoveOrdering, TableEntry, TranspositionTable, INFINITY, MAX_PLY};
+
+/// The number of nodes between two checks of the clock.
+const CLOCK_CHECK_INTERVAL: u64 = 1024;
+
+/// The outcome of a search.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchResult {
+    /// The principal variation, i.e. the expected line of play.
+    pv: Vec<Move>,
+    /// The score of the position from the moving team's perspective.
+    score: i32,
+    /// The depth of the deepest completed iteration.
+    depth: usize,
+    /// The number of visited nodes.
+    pub(super) nodes: u64,
+}
+
+impl SearchResult {
+    /// The best move found, if any.
+    #[inline]
+    pub fn best_move(&self) -> Option<Move> { self.pv.first().copied() }
+
+    /// The principal variation, i.e. the expected line of play.
+    #[inline]
+    pub fn pv(&self) -> &[Move] { &self.pv }
+
+    /// The score of the position from the moving team's perspective.
+    #[inline]
+    pub fn score(&self) -> i32 { self.score }
+
+    /// The depth of the deepest completed iteration.
+    #[inline]
+    pub fn depth(&self) -> usize { self.depth }
+
+    /// The number of visited nodes.
+    #[inline]
+    pub fn nodes(&self) -> u64 { self.nodes }
+}
+
+/// A negamax alpha-beta search using iterative deepening and
+/// principal variation search on top of a pluggable evaluation.
+pub struct AlphaBeta<E> {
+    evaluator: E,
+    max_depth: usize,
+    /// The depth of the first iteration.
+    first_depth: usize,
+    time_limit: Option<Duration>,
+    time: Option<TimeManager>,
+    nodes: u64,
+    stopped: bool,
+    /// A flag through which other threads can stop the search.
+    stop_flag: Option<Arc<AtomicBool>>,
+    quiescence: bool,
+    table: Arc<TranspositionTable>,
+    /// Whether each search starts a new generation of the table.
+    ages_table: bool,
+    ordering: MoveOrdering,
+    /// Reused move buffers, one per ply.
+    move_buffers: Vec<Vec<Move>>,
+}
+
+impl<E> AlphaBeta<E> where E: Evaluator {
+    /// Creates a new search using the given evaluator.
+    pub fn new(evaluator: E) -> Self {
+        Self::with_table(evaluator, Arc::new(TranspositionTable::default()))
+    }
+
+    /// Creates a new search using the given evaluator and
+    /// transposition table, which may be shared with other searches.
+    pub fn with_table(evaluator: E, table: Arc<TranspositionTable>) -> Self {
+        Self {
+            evaluator,
+            max_depth: MAX_PLY,
+            first_depth: 1,
+            time_limit: None,
+            time: None,
+            nodes: 0,
+            stopped: false,
+            stop_flag: None,
+            quiescence: true,
+            table,
+            ages_table: true,
+            ordering: MoveOrdering::new(),
+            move_buffers: vec![Vec::new(); MAX_PLY],
+        }
+    }
+
+    /// Sets the maximum depth to search to.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth.min(MAX_PLY);
+        self
+    }
+
+    /// Sets the time after which the search is stopped. The
+    /// deepest completed iteration determines the result.
+    pub fn time_limit(mut self, time_limit: Option<Duration>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    /// Sets a flag that stops the search once set, e.g. by another
+    /// thread. The deepest completed iteration determines the result.
+    pub fn stop_flag(mut self, stop_flag: Arc<AtomicBool>) -> Self {
+        self.stop_flag = Some(stop_flag);
+        self
+    }
+
+    /// Sets whether leaves are extended by a quiescence search
+    /// over captures and amber moves (enabled by default).
+    pub fn quiescence(mut self, quiescence: bool) -> Self {
+        self.quiescence = quiescence;
+        self
+    }
+
+    /// Sets the transposition table, which may be shared
+    /// with other searches.
+    pub fn transposition_table(mut self, table: Arc<TranspositionTable>) -> Self {
+        self.table = table;
+        self
+    }
+
+    /// Sets whether each search starts a new generation of the
+    /// table (enabled by default). Searches sharing a table within
+    /// the same move should start a generation only once.
+    pub fn ages_table(mut self, ages_table: bool) -> Self {
+        self.ages_table = ages_table;
+        self
+    }
+
+    /// Creates a helper search, e.g. for a parallel search or for
+    /// pondering, with the same settings and transposition table
+    /// (without ageing it), stopping once the given flag is set.
+    /// Odd helpers skip the first iteration to diversify the search.
+    pub(crate) fn helper(&self, index: usize, stop_flag: Arc<AtomicBool>) -> Self where E: Clone {
+        let mut helper = Self::with_table(self.evaluator.clone(), self.table.clone())
+            .max_depth(self.max_depth)
+            .quiescence(self.quiescence)
+            .ages_table(false)
+            .stop_flag(stop_flag);
+        helper.first_depth = 1 + index % 2;
+        helper
+    }
+
+    /// The transposition table.
+    #[inline]
+    pub fn table(&self) -> &Arc<TranspositionTable> { &self.table }
+
+    /// The evaluator used at the leaves.
+    #[inline]
+    pub fn evaluator(&self) -> &E { &self.evaluator }
+
+    /// Searches the best move in the given state by
+    /// iteratively deepening the search.
+    pub fn search(&mut self, state: &State) -> SearchResult {
+        self.time = self.time_limit.map(TimeManager::with_limit);
+        self.run(state)
+    }
+
+    /// Searches the best move in the given state, stopping at the
+    /// time manager's deadline (or earlier if a time limit is set).
+    pub fn search_with(&mut self, state: &State, time: &TimeManager) -> SearchResult {
+        self.time = Some(time.limited(self.time_limit));
+        self.run(state)
+    }
+
+    fn run(&mut self, state: &State) -> SearchResult {
+        self.nodes = 0;
+        self.stopped = false;
+        if self.ages_table {
+            self.table.new_search();
+        }
+        self.ordering.new_search();
+
+        let mut state = state.clone();
+        let mut result = SearchResult::default();
+        if state.current_team().is_none() {
+            return result;
+        }
+
+        for depth in self.first_depth..=self.max_depth {
+            let mut pv = Vec::new();
+            let score = self.negamax(&mut state, depth, 0, -INFINITY, INFINITY, &mut pv);
+            if self.stopped && !result.pv.is_empty() {
+                break;
+            }
+            debug!("Depth {}: score {}, {} nodes, pv {}", depth, score, self.nodes, pv.iter().map(|m| m.notation()).collect::<Vec<_>>().join(" "));
+            result = SearchResult { pv, score, depth, nodes: self.nodes };
+            if self.stopped || is_win_score(score) || result.pv.is_empty() {
+                break;
+            }
+            if self.time.is_some_and(|t| !t.can_start_iteration()) {
+                debug!("Not enough time left for depth {}", depth + 1);
+                break;
+            }
+        }
+
+        result.nodes = self.nodes;
+        result
+    }
+
+    /// Checks whether the search should be stopped, either due
+    /// to the deadline or due to the stop flag.
+    fn check_clock(&mut self) {
+        if self.nodes.is_multiple_of(CLOCK_CHECK_INTERVAL) {
+            let expired = self.time.is_some_and(|t| t.is_expired());
+            let cancelled = self.stop_flag.as_ref().is_some_and(|f| f.load(Ordering::Relaxed));
+            self.stopped |= expired || cancelled;
+        }
+    }
+
+    /// Searches the state with a (fail-soft) principal variation search,
+    /// returning the score from the current team's perspective and
+    /// storing the principal variation in `pv`.
+    fn negamax(&mut self, state: &mut State, depth: usize, ply: usize, mut alpha: i32, beta: i32, pv: &mut Vec<Move>) -> i32 {
+        self.nodes += 1;
+        self.check_clock();
+        if self.stopped {
+            return 0;
+        }
+
+        let team = state.current_team().expect("Cannot search a state without a current team");
+        if let Some(score) = terminal_score(state, team, ply) {
+            return score;
+        }
+        if depth == 0 || ply >= MAX_PLY {
+            return if self.quiescence {
+                self.quiescence_search(state, ply, alpha, beta)
+            } else {
+                self.evaluator.evaluate(state, team)
+            };
+        }
+
+        // Probe the transposition table, cutting off outside of the
+        // principal variation to keep it intact. The turn is part of the
+        // key, since it determines how close the round limit is.
+        let key = state.zobrist() ^ zobrist::turn_key(state.turn());
+        let is_pv = beta - alpha > 1;
+        let entry = self.table.probe(key);
+        if let Some(entry) = entry.filter(|e| !is_pv && e.depth() >= depth) {
+            let score = score_from_table(entry.score(), ply);
+            match entry.bound() {
+                Bound::Exact => return score,
+                Bound::Lower if score >= beta => return score,
+                Bound::Upper if score <= alpha => return score,
+                _ => {},
+            }
+        }
+
+        let mut moves = std::mem::take(&mut self.move_buffers[ply]);
+        state.fill_moves(&mut moves);
+        self.ordering.order(state, &mut moves, entry.and_then(|e| e.best_move()), ply);
+
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        let mut best_move = None;
+        let mut child_pv = Vec::new();
+        for (i, &m) in moves.iter().enumerate() {
+            let undo = state.perform(m);
+            child_pv.clear();
+            let score = if i == 0 {
+                -self.negamax(state, depth - 1, ply + 1, -beta, -alpha, &mut child_pv)
+            } else {
+                let score = -self.negamax(state, depth - 1, ply + 1, -alpha - 1, -alpha, &mut child_pv);
+                if score > alpha && score < beta {
+                    child_pv.clear();
+                    -self.negamax(state, depth - 1, ply + 1, -beta, -alpha, &mut child_pv)
+                } else {
+                    score
+                }
+            };
+            state.unmake(undo);
+            if self.stopped {
+                break;
+            }
+
+            if score > best {
+                best = score;
+                best_move = Some(m);
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(m);
+                    pv.extend_from_slice(&child_pv);
+                }
+            }
+            if alpha >= beta {
+                self.ordering.record_cutoff(state, m, depth, ply);
+                break;
+            }
+        }
+        self.move_buffers[ply] = moves;
+        if self.stopped {
+            return 0;
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.store(key, TableEntry::new(depth, bound, score_to_table(best, ply), best_move));
+
+        best
+    }
+
+    /// Resolves the tactical moves at a leaf by searching only captures
+    /// and amber moves, assuming that the current team may otherwise
+    /// keep the static evaluation ('stand pat').
+    fn quiescence_search(&mut self, state: &mut State, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        self.check_clock();
+        if self.stopped {
+            return 0;
+        }
+
+        let team = state.current_team().expect("Cannot search a state without a current team");
+        if let Some(score) = terminal_score(state, team, ply) {
+            return score;
+        }
+        let stand_pat = self.evaluator.evaluate(state, team);
+        if stand_pat >= beta || ply >= MAX_PLY {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        let mut moves = std::mem::take(&mut self.move_buffers[ply]);
+        moves.clear();
+        moves.extend(state.capture_moves());
+        self.ordering.order(state, &mut moves, None, ply);
+
+        let mut best = stand_pat;
+        for &m in &moves {
+            let undo = state.perform(m);
+            let score = -self.quiescence_search(state, ply + 1, -beta, -alpha);
+            state.unmake(undo);
+            if self.stopped {
+                break;
+            }
+
+            if score > best {
+                best = score;
+                alpha = alpha.max(score);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        self.move_buffers[ply] = moves;
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, sync::{atomic::AtomicBool, Arc}};
+
+    use crate::{eval::{Evaluator, SimpleEvaluator}, game::{Move, State, Vec2}, search::{fixtures, terminal_score, AlphaBeta, WIN_SCORE}};
+
+    /// A plain minimax search without pruning for reference.
+    fn minimax(state: &State, depth: usize, ply: usize) -> i32 {
+        let team = state.current_team().unwrap();
+        if let Some(score) = terminal_score(state, team, ply) {
+            return score;
+        }
+        if depth == 0 {
+            return SimpleEvaluator.evaluate(state, team);
+        }
+        state.moves()
+            .map(|m| -minimax(&state.child(m), depth - 1, ply + 1))
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_finds_winning_amber() {
+        let (state, winning_move) = fixtures::winning_amber();
+        let result = AlphaBeta::new(SimpleEvaluator).max_depth(4).search(&state);
+        assert_eq!(result.best_move(), Some(winning_move));
+        assert_eq!(result.score(), WIN_SCORE - 2);
+        assert_eq!(result.depth(), 2);
+    }
+
+    #[test]
+    fn test_matches_minimax() {
+        for notation in ["5m2/HH5s/S6R[2]/8/8/MS[2]4s1/2R1r2h/M6h 1:0 20 1 f5e7", "R6r/H6s/M6m/H6s/S6h/M6m/S6h/R6r 0:0 0 1 -"] {
+            let state = State::from_str(notation).unwrap();
+            let result = AlphaBeta::new(SimpleEvaluator).max_depth(3).quiescence(false).search(&state);
+            assert_eq!(result.depth(), 3);
+            assert_eq!(result.score(), minimax(&state, 3, 0), "Score of {}", notation);
+            assert_eq!(result.pv().len(), 3);
+            assert!(state.validate_move(result.best_move().unwrap()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_table_reuse() {
+        let state = State::from_str("5m2/HH5s/S6R[2]/8/8/MS[2]4s1/2R1r2h/M6h 1:0 20 1 f5e7").unwrap();
+        let mut search = AlphaBeta::new(SimpleEvaluator).max_depth(4);
+        let cold = search.search(&state);
+        let warm = search.search(&state);
+        assert_eq!(cold.score(), warm.score());
+        assert!(warm.nodes() < cold.nodes());
+    }
+
+    #[test]
+    fn test_table_turns() {
+        // The same position close to the round limit has a different
+        // horizon, so entries from earlier turns must not be reused
+        let early = State::from_str("R6r/H6s/M6m/H6s/S6h/M6m/S6h/R6r 0:0 9 1 -").unwrap();
+        let late = State::from_str("R6r/H6s/M6m/H6s/S6h/M6m/S6h/R6r 0:0 57 1 -").unwrap();
+        let mut search = AlphaBeta::new(SimpleEvaluator).max_depth(3);
+        search.search(&early);
+        let warm = search.search(&late);
+        let cold = AlphaBeta::new(SimpleEvaluator).max_depth(3).search(&late);
+        assert_eq!(warm.score(), cold.score());
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let state = State::initial(9);
+        let first = AlphaBeta::new(SimpleEvaluator).max_depth(4).search(&state);
+        let second = AlphaBeta::new(SimpleEvaluator).max_depth(4).search(&state);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_quiescence() {
+        // Capturing the moewe looks good at the horizon, but
+        // loses the tower (and an amber) to the recapture
+        let state = State::from_str("H7/8/8/3Mmm[2]2/8/8/8/7h 0:0 0 1 -").unwrap();
+        let capture = Move::new(Vec2::new(3, 3), Vec2::new(4, 3));
+        let shallow = AlphaBeta::new(SimpleEvaluator).max_depth(1).quiescence(false).search(&state);
+        assert_eq!(shallow.best_move(), Some(capture));
+        let quiet = AlphaBeta::new(SimpleEvaluator).max_depth(1).search(&state);
+        assert_ne!(quiet.best_move(), Some(capture));
+        assert!(quiet.score() < shallow.score());
+    }
+
+    #[test]
+    fn test_stop_flag() {
+        let state = State::initial(3);
+        let stop = Arc::new(AtomicBool::new(true));
+        let result = AlphaBeta::new(SimpleEvaluator).stop_flag(stop).search(&state);
+        assert!(result.depth() >= 1 && result.depth() < 4);
+        assert!(state.validate_move(result.best_move().unwrap()).is_ok());
+    }
+}
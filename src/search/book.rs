@@ -0,0 +1,195 @@
+use std::{collections::HashMap, fmt, fs, path::Path, str::FromStr};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{game::{GameRecord, Move, State}, util::{SCError, SCResult}};
+
+/// The default number of plies per game added to an opening book.
+pub const DEFAULT_BOOK_PLIES: usize = 12;
+
+/// A move in an opening book, weighted by how often
+/// (and how successfully) it was played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BookMove {
+    m: Move,
+    weight: u32,
+}
+
+impl BookMove {
+    /// The move.
+    #[inline]
+    pub fn get(&self) -> Move { self.m }
+
+    /// The move's weight.
+    #[inline]
+    pub fn weight(&self) -> u32 { self.weight }
+}
+
+/// An opening book mapping positions (by their Zobrist hash)
+/// to weighted moves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpeningBook {
+    entries: HashMap<u64, Vec<BookMove>>,
+}
+
+impl OpeningBook {
+    /// Creates an empty book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a book from the first plies of the given games. Moves
+    /// of the winning team count twice, moves of the losing team
+    /// are left out.
+    pub fn from_records<'a>(records: impl IntoIterator<Item=&'a GameRecord>, max_plies: usize) -> Self {
+        let mut book = Self::new();
+        for record in records {
+            let winner = record.outcome().map(|o| o.winner());
+            for (state, m) in record.positions().take(max_plies) {
+                let weight = match winner {
+                    Some(Some(team)) if state.current_team() == Some(team) => 2,
+                    Some(Some(_)) => 0,
+                    _ => 1,
+                };
+                book.add(&state, m, weight);
+            }
+        }
+        book
+    }
+
+    /// Reads a book from the given file.
+    pub fn load(path: impl AsRef<Path>) -> SCResult<Self> {
+        Self::from_str(&fs::read_to_string(path)?)
+    }
+
+    /// Writes the book to the given file.
+    pub fn save(&self, path: impl AsRef<Path>) -> SCResult<()> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    /// The number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the book contains no positions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds the given weight to a move in the given state.
+    /// Moves with zero weight are ignored.
+    pub fn add(&mut self, state: &State, m: Move, weight: u32) {
+        self.insert(state.zobrist(), m, weight);
+    }
+
+    fn insert(&mut self, key: u64, m: Move, weight: u32) {
+        if weight == 0 {
+            return;
+        }
+        let moves = self.entries.entry(key).or_default();
+        match moves.iter_mut().find(|bm| bm.m == m) {
+            Some(bm) => bm.weight = bm.weight.saturating_add(weight),
+            None => moves.push(BookMove { m, weight }),
+        }
+    }
+
+    /// The book moves in the given state. Moves that are not
+    /// possible (due to hash collisions) are filtered out.
+    pub fn moves(&self, state: &State) -> Vec<BookMove> {
+        self.entries.get(&state.zobrist())
+            .into_iter()
+            .flatten()
+            .filter(|bm| state.validate_move(bm.m).is_ok())
+            .copied()
+            .collect()
+    }
+
+    /// Picks one of the book moves in the given state at
+    /// random, proportionally to their weights.
+    pub fn choose(&self, state: &State, rng: &mut impl Rng) -> Option<Move> {
+        self.moves(state)
+            .choose_weighted(rng, |bm| bm.weight)
+            .ok()
+            .map(|bm| bm.m)
+    }
+}
+
+/// Formats the book as `hash move weight` lines, sorted by hash.
+impl fmt::Display for OpeningBook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut keys = self.entries.keys().collect::<Vec<_>>();
+        keys.sort();
+        for key in keys {
+            for bm in &self.entries[key] {
+                writeln!(f, "{:016x} {} {}", key, bm.m.notation(), bm.weight)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses `hash move weight` lines, ignoring
+/// empty lines and lines starting with `#`.
+impl FromStr for OpeningBook {
+    type Err = SCError;
+
+    fn from_str(s: &str) -> SCResult<Self> {
+        let mut book = Self::new();
+        for line in s.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            if fields.len() != 3 {
+                return Err(SCError::InvalidNotation(format!("Expected 'hash move weight', got '{}'", line)));
+            }
+            let key = u64::from_str_radix(fields[0], 16)?;
+            book.insert(key, Move::from_notation(fields[1])?, fields[2].parse()?);
+        }
+        Ok(book)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{game::{GameRecord, State}, search::OpeningBook};
+
+    #[test]
+    fn test_from_records() {
+        let records = (0..4)
+            .map(|i| GameRecord::play(State::initial(i % 2), |state| state.moves().nth(i as usize).unwrap_or_else(|| state.moves().next().unwrap())))
+            .collect::<Vec<_>>();
+        let book = OpeningBook::from_records(&records, 4);
+        assert!(!book.is_empty());
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for record in &records {
+            let winner = record.outcome().unwrap().winner();
+            let (start, first) = record.positions().next().unwrap();
+            let moves = book.moves(&start);
+            if winner.is_none_or(|w| start.current_team() == Some(w)) {
+                assert!(moves.iter().any(|bm| bm.get() == first));
+            }
+            if let Some(m) = book.choose(&start, &mut rng) {
+                assert!(moves.iter().any(|bm| bm.get() == m));
+            }
+        }
+        assert!(book.moves(&State::initial(7)).is_empty());
+    }
+
+    #[test]
+    fn test_notation() {
+        let record = GameRecord::play(State::initial(3), |state| state.moves().next().unwrap());
+        let mut book = OpeningBook::new();
+        for (state, m) in record.positions().take(6) {
+            book.add(&state, m, 3);
+        }
+        book.add(record.start(), record.moves()[0], 2);
+        assert_eq!(book.moves(record.start())[0].weight(), 5);
+        assert_eq!(OpeningBook::from_str(&book.to_string()).unwrap(), book);
+        assert!(OpeningBook::from_str("abc a1b2").is_err());
+    }
+}
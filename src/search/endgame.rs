@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use log::debug;
+
+use crate::{game::{Move, State}, util::TimeManager};
+
+/// The default maximum number of pieces in positions to solve.
+pub const DEFAULT_MAX_PIECES: usize = 5;
+/// The default maximum number of nodes searched per solve.
+pub const DEFAULT_NODE_LIMIT: u64 = 2_000_000;
+/// The number of nodes between two checks of the clock.
+const CLOCK_CHECK_INTERVAL: u64 = 1024;
+/// The maximum number of positions kept between solves.
+const MAX_TABLE_SIZE: usize = 4_000_000;
+
+/// The game-theoretic value of a position from the moving team's perspective.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EndgameValue {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl EndgameValue {
+    /// The value from the opponent's perspective.
+    pub fn opponent(self) -> Self {
+        match self {
+            Self::Loss => Self::Win,
+            Self::Draw => Self::Draw,
+            Self::Win => Self::Loss,
+        }
+    }
+}
+
+/// The result of solving a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Solution {
+    value: EndgameValue,
+    best_move: Option<Move>,
+    nodes: u64,
+}
+
+impl Solution {
+    /// The game-theoretic value from the moving team's perspective.
+    #[inline]
+    pub fn value(&self) -> EndgameValue { self.value }
+
+    /// A move achieving the value, if the game is not over.
+    #[inline]
+    pub fn best_move(&self) -> Option<Move> { self.best_move }
+
+    /// The number of visited nodes.
+    #[inline]
+    pub fn nodes(&self) -> u64 { self.nodes }
+}
+
+/// An exact solver that searches positions with few pieces exhaustively
+/// until the end of the game (at the latest at the round limit). Solved
+/// positions are memoized by their hash and turn, since the turn
+/// determines how many rounds are left.
+pub struct EndgameSolver {
+    max_pieces: usize,
+    node_limit: u64,
+    nodes: u64,
+    time: Option<TimeManager>,
+    table: HashMap<(u64, usize), (EndgameValue, Option<Move>)>,
+}
+
+impl EndgameSolver {
+    /// Creates a solver with the default piece threshold and node limit.
+    pub fn new() -> Self {
+        Self {
+            max_pieces: DEFAULT_MAX_PIECES,
+            node_limit: DEFAULT_NODE_LIMIT,
+            nodes: 0,
+            time: None,
+            table: HashMap::new(),
+        }
+    }
+
+    /// Sets the maximum number of pieces in positions to solve.
+    pub fn max_pieces(mut self, max_pieces: usize) -> Self {
+        self.max_pieces = max_pieces;
+        self
+    }
+
+    /// Sets the maximum number of nodes searched per solve,
+    /// after which the solver gives up.
+    pub fn node_limit(mut self, node_limit: u64) -> Self {
+        self.node_limit = node_limit;
+        self
+    }
+
+    /// Checks whether the state has few enough pieces to be solved.
+    pub fn is_applicable(&self, state: &State) -> bool {
+        state.board().piece_count() <= self.max_pieces
+    }
+
+    /// Solves the given state, returning `None` if it has too many
+    /// pieces or could not be solved within the node limit.
+    pub fn solve(&mut self, state: &State) -> Option<Solution> {
+        self.time = None;
+        self.run(state)
+    }
+
+    /// Solves the given state like `solve`, but also gives up
+    /// at the time manager's deadline.
+    pub fn solve_with(&mut self, state: &State, time: &TimeManager) -> Option<Solution> {
+        self.time = Some(*time);
+        self.run(state)
+    }
+
+    fn run(&mut self, state: &State) -> Option<Solution> {
+        if !self.is_applicable(state) {
+            return None;
+        }
+        if self.table.len() > MAX_TABLE_SIZE {
+            self.table.clear();
+        }
+        self.nodes = 0;
+        let mut state = state.clone();
+        let solved = self.solve_node(&mut state);
+        debug!("Endgame solver: {:?} after {} nodes", solved, self.nodes);
+        let (value, best_move) = solved?;
+        Some(Solution { value, best_move, nodes: self.nodes })
+    }
+
+    /// Solves the state exactly, returning `None` if the search was aborted.
+    fn solve_node(&mut self, state: &mut State) -> Option<(EndgameValue, Option<Move>)> {
+        self.nodes += 1;
+        if self.nodes > self.node_limit {
+            return None;
+        }
+        if self.nodes.is_multiple_of(CLOCK_CHECK_INTERVAL) && self.time.is_some_and(|t| t.is_expired()) {
+            return None;
+        }
+
+        let team = state.current_team()?;
+        if let Some(outcome) = state.outcome() {
+            let value = match outcome.winner() {
+                Some(winner) if winner == team => EndgameValue::Win,
+                Some(_) => EndgameValue::Loss,
+                None => EndgameValue::Draw,
+            };
+            return Some((value, None));
+        }
+
+        let key = (state.zobrist(), state.turn());
+        if let Some(&solved) = self.table.get(&key) {
+            return Some(solved);
+        }
+
+        // Try tactical moves first, since they tend to decide the game
+        let board = state.board();
+        let mut moves = state.possible_moves();
+        moves.sort_by_cached_key(|&m| (board.amber_gain(m) == 0, !board.is_capture(m)));
+
+        let mut best = (EndgameValue::Loss, None);
+        for m in moves {
+            let undo = state.perform(m);
+            let solved = self.solve_node(state);
+            state.unmake(undo);
+            let value = solved?.0.opponent();
+            if best.1.is_none() || value > best.0 {
+                best = (value, Some(m));
+                if value == EndgameValue::Win {
+                    break;
+                }
+            }
+        }
+
+        self.table.insert(key, best);
+        Some(best)
+    }
+}
+
+impl Default for EndgameSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{game::State, search::{fixtures, EndgameSolver, EndgameValue}};
+
+    /// Solves the state by plain minimax for reference.
+    fn minimax(state: &State) -> EndgameValue {
+        let team = state.current_team().unwrap();
+        if let Some(outcome) = state.outcome() {
+            return match outcome.winner() {
+                Some(winner) if winner == team => EndgameValue::Win,
+                Some(_) => EndgameValue::Loss,
+                None => EndgameValue::Draw,
+            };
+        }
+        state.moves().map(|m| minimax(&state.child(m)).opponent()).max().unwrap()
+    }
+
+    #[test]
+    fn test_finds_winning_amber() {
+        let (state, winning) = fixtures::winning_amber();
+        let solution = EndgameSolver::new().solve(&state).unwrap();
+        assert_eq!(solution.value(), EndgameValue::Win);
+        assert_eq!(solution.best_move(), Some(winning));
+    }
+
+    #[test]
+    fn test_matches_minimax() {
+        let mut solver = EndgameSolver::new();
+        for notation in ["7h/8/2m5/8/4S3/8/8/H7 0:0 58 1 -", "8/2r5/3M4/8/1h6/8/6S1/8 1:1 57 1 -", "7s/8/8/3r4/2H5/8/8/R7 0:0 56 1 -"] {
+            let state = State::from_str(notation).unwrap();
+            let solution = solver.solve(&state).unwrap();
+            assert_eq!(solution.value(), minimax(&state), "Value of {}", notation);
+            let child = state.child(solution.best_move().unwrap());
+            let after = EndgameSolver::new().solve(&child).unwrap().value().opponent();
+            assert_eq!(after, solution.value());
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let state = State::initial(1);
+        assert!(EndgameSolver::new().solve(&state).is_none());
+        let state = State::from_str("7h/8/2m5/8/4S3/8/8/H7 0:0 0 1 -").unwrap();
+        assert!(EndgameSolver::new().node_limit(100).solve(&state).is_none());
+    }
+}
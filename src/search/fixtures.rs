@@ -0,0 +1,11 @@
+use std::str::FromStr;
+
+use crate::game::{Move, State, Vec2};
+
+/// A position in which the first team wins at once by stacking its
+/// tower onto the moewe (earning its second amber), along with the
+/// winning move. Shared by the tests of the search algorithms.
+pub fn winning_amber() -> (State, Move) {
+    let state = State::from_str("8/1R[2]6/3m4/8/8/8/8/M6m 1:0 0 1 -").unwrap();
+    (state, Move::new(Vec2::new(1, 1), Vec2::new(3, 2)))
+}
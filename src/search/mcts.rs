@@ -0,0 +1,337 @@
+use std::time::Duration;
+
+use log::debug;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+use crate::{game::{Move, State, Team}, util::TimeManager};
+
+/// Picks moves during the simulation phase of a Monte Carlo tree search.
+pub trait RolloutPolicy {
+    /// Picks one of the given (non-empty) moves in the given state.
+    fn choose(&self, state: &State, moves: &[Move], rng: &mut StdRng) -> Move;
+}
+
+/// Picks moves uniformly at random.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RandomRollout;
+
+impl RolloutPolicy for RandomRollout {
+    fn choose(&self, _state: &State, moves: &[Move], rng: &mut StdRng) -> Move {
+        *moves.choose(rng).expect("No moves to choose from")
+    }
+}
+
+/// Picks a capturing move with the given probability if
+/// there is one, otherwise picks moves at random.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaptureRollout {
+    pub capture_probability: f64,
+}
+
+impl Default for CaptureRollout {
+    fn default() -> Self {
+        Self { capture_probability: 0.8 }
+    }
+}
+
+impl RolloutPolicy for CaptureRollout {
+    fn choose(&self, state: &State, moves: &[Move], rng: &mut StdRng) -> Move {
+        if rng.gen_bool(self.capture_probability) {
+            let captures: Vec<Move> = moves.iter().copied().filter(|m| state.board().get(m.to()).is_some()).collect();
+            if let Some(&m) = captures.choose(rng) {
+                return m;
+            }
+        }
+        RandomRollout.choose(state, moves, rng)
+    }
+}
+
+/// A node in the search tree.
+#[derive(Debug, Clone)]
+struct Node {
+    /// The move leading to this node (`None` for the root).
+    m: Option<Move>,
+    /// The team that performed the move leading to this node.
+    team: Option<Team>,
+    /// The Zobrist hash of the node's state.
+    hash: u64,
+    children: Vec<usize>,
+    untried: Vec<Move>,
+    visits: u32,
+    /// The accumulated reward from the perspective of `team`.
+    reward: f64,
+}
+
+impl Node {
+    fn new(m: Option<Move>, team: Option<Team>, state: &State) -> Self {
+        let untried = if state.is_over() { Vec::new() } else { state.possible_moves() };
+        Self { m, team, hash: state.zobrist(), children: Vec::new(), untried, visits: 0, reward: 0.0 }
+    }
+}
+
+/// The outcome of a Monte Carlo tree search.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MctsResult {
+    best_move: Option<Move>,
+    iterations: u64,
+    visits: u32,
+    win_rate: f64,
+    reused_visits: u32,
+}
+
+impl MctsResult {
+    /// The most visited move at the root, if any.
+    #[inline]
+    pub fn best_move(&self) -> Option<Move> { self.best_move }
+
+    /// The number of iterations performed by this search.
+    #[inline]
+    pub fn iterations(&self) -> u64 { self.iterations }
+
+    /// The number of visits of the best move.
+    #[inline]
+    pub fn visits(&self) -> u32 { self.visits }
+
+    /// The average reward of the best move for the moving team.
+    #[inline]
+    pub fn win_rate(&self) -> f64 { self.win_rate }
+
+    /// The number of root visits reused from the previous search.
+    #[inline]
+    pub fn reused_visits(&self) -> u32 { self.reused_visits }
+}
+
+/// A Monte Carlo tree search using UCT for selection and a
+/// configurable rollout policy. The subtree below the opponent's
+/// reply is kept between consecutive searches.
+pub struct Mcts<P> {
+    policy: P,
+    exploration: f64,
+    iterations: Option<u64>,
+    time_limit: Option<Duration>,
+    rng: StdRng,
+    nodes: Vec<Node>,
+    /// The move chosen by the previous search.
+    last_choice: Option<Move>,
+}
+
+impl<P> Mcts<P> where P: RolloutPolicy {
+    /// Creates a new search with the given rollout policy and a budget
+    /// of 10000 iterations, seeding the random number generator used
+    /// for expansion and rollouts.
+    pub fn new(policy: P, seed: u64) -> Self {
+        Self {
+            policy,
+            exploration: std::f64::consts::SQRT_2,
+            iterations: Some(10_000),
+            time_limit: None,
+            rng: StdRng::seed_from_u64(seed),
+            nodes: Vec::new(),
+            last_choice: None,
+        }
+    }
+
+    /// Sets the exploration constant of the UCT formula.
+    pub fn exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Sets the maximum number of iterations per search.
+    pub fn iterations(mut self, iterations: Option<u64>) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the maximum time per search.
+    pub fn time_limit(mut self, time_limit: Option<Duration>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    /// Searches the best move in the given state.
+    pub fn search(&mut self, state: &State) -> MctsResult {
+        let time = self.time_limit.map(TimeManager::with_limit);
+        self.run(state, time)
+    }
+
+    /// Searches the best move in the given state, stopping at the
+    /// time manager's deadline (or earlier if a time limit is set).
+    pub fn search_with(&mut self, state: &State, time: &TimeManager) -> MctsResult {
+        self.run(state, Some(time.limited(self.time_limit)))
+    }
+
+    fn run(&mut self, state: &State, time: Option<TimeManager>) -> MctsResult {
+        if state.current_team().is_none() {
+            return MctsResult::default();
+        }
+
+        let reused_visits = self.reuse_subtree(state);
+        let mut iterations = 0;
+
+        while self.iterations.is_none_or(|max| iterations < max) && time.is_none_or(|t| !t.is_expired()) {
+            if self.nodes[0].untried.is_empty() && self.nodes[0].children.is_empty() {
+                break;
+            }
+            self.iterate(state.clone());
+            iterations += 1;
+        }
+
+        let root = &self.nodes[0];
+        let best = root.children.iter().copied().max_by_key(|&c| self.nodes[c].visits);
+        self.last_choice = best.and_then(|c| self.nodes[c].m);
+        let result = MctsResult {
+            best_move: self.last_choice,
+            iterations,
+            visits: best.map_or(0, |c| self.nodes[c].visits),
+            win_rate: best.map_or(0.0, |c| self.nodes[c].reward / self.nodes[c].visits.max(1) as f64),
+            reused_visits,
+        };
+        debug!("MCTS: {} iterations, {} nodes, {:?}", iterations, self.nodes.len(), result);
+        result
+    }
+
+    /// Replaces the tree with the subtree below the previously chosen
+    /// move and the opponent's reply if the given state is found there,
+    /// otherwise starts a fresh tree. Returns the reused root visits.
+    fn reuse_subtree(&mut self, state: &State) -> u32 {
+        let child_by_move = |nodes: &[Node], parent: usize, m: Option<Move>| m.and_then(|m| nodes[parent].children
+            .iter()
+            .copied()
+            .find(|&c| nodes[c].m == Some(m)));
+        let new_root = if self.nodes.is_empty() {
+            None
+        } else {
+            child_by_move(&self.nodes, 0, self.last_choice)
+                .and_then(|c| child_by_move(&self.nodes, c, state.last_move()))
+                .filter(|&g| self.nodes[g].hash == state.zobrist())
+        };
+
+        match new_root {
+            Some(root) => {
+                let mut nodes = Vec::new();
+                let mut queue = vec![(root, None)];
+                while let Some((old, parent)) = queue.pop() {
+                    let index = nodes.len();
+                    let mut node = self.nodes[old].clone();
+                    let children = std::mem::take(&mut node.children);
+                    nodes.push(node);
+                    if let Some(parent) = parent {
+                        let parent: &mut Node = &mut nodes[parent];
+                        parent.children.push(index);
+                    }
+                    queue.extend(children.into_iter().map(|c| (c, Some(index))));
+                }
+                self.nodes = nodes;
+            },
+            None => self.nodes = vec![Node::new(None, None, state)],
+        }
+        self.nodes[0].visits
+    }
+
+    /// Performs a single selection, expansion, simulation and
+    /// backpropagation step starting with the root's state.
+    fn iterate(&mut self, mut state: State) {
+        let mut path = vec![0];
+        let mut current = 0;
+
+        // Selection
+        while self.nodes[current].untried.is_empty() && !self.nodes[current].children.is_empty() {
+            current = self.select_child(current);
+            state.perform(self.nodes[current].m.unwrap());
+            path.push(current);
+        }
+
+        // Expansion
+        if !self.nodes[current].untried.is_empty() {
+            let untried = &mut self.nodes[current].untried;
+            let m = untried.swap_remove(self.rng.gen_range(0..untried.len()));
+            let team = state.current_team();
+            state.perform(m);
+            let child = self.nodes.len();
+            self.nodes.push(Node::new(Some(m), team, &state));
+            self.nodes[current].children.push(child);
+            path.push(child);
+        }
+
+        // Simulation
+        let mut moves = Vec::new();
+        let winner = loop {
+            if let Some(outcome) = state.outcome() {
+                break outcome.winner();
+            }
+            state.fill_moves(&mut moves);
+            if moves.is_empty() {
+                break None;
+            }
+            let m = self.policy.choose(&state, &moves, &mut self.rng);
+            state.perform(m);
+        };
+
+        // Backpropagation
+        for &i in &path {
+            let node = &mut self.nodes[i];
+            node.visits += 1;
+            node.reward += match winner {
+                None => 0.5,
+                Some(team) if Some(team) == node.team => 1.0,
+                Some(_) => 0.0,
+            };
+        }
+    }
+
+    /// Selects the child maximizing the UCT value.
+    fn select_child(&self, parent: usize) -> usize {
+        let log_visits = (self.nodes[parent].visits.max(1) as f64).ln();
+        *self.nodes[parent].children
+            .iter()
+            .max_by(|&&a, &&b| self.uct(a, log_visits).total_cmp(&self.uct(b, log_visits)))
+            .expect("No children to select from")
+    }
+
+    fn uct(&self, node: usize, parent_log_visits: f64) -> f64 {
+        let node = &self.nodes[node];
+        let visits = node.visits.max(1) as f64;
+        node.reward / visits + self.exploration * (parent_log_visits / visits).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{game::State, search::{fixtures, CaptureRollout, Mcts, MctsResult, RandomRollout}};
+
+    #[test]
+    fn test_finds_winning_amber() {
+        let (state, winning_move) = fixtures::winning_amber();
+        let result = Mcts::new(RandomRollout, 1).iterations(Some(2000)).search(&state);
+        assert_eq!(result.best_move(), Some(winning_move));
+        assert!(result.win_rate() > 0.9);
+    }
+
+    #[test]
+    fn test_reuses_subtree() {
+        let mut mcts = Mcts::new(CaptureRollout::default(), 2).iterations(Some(500));
+        let mut state = State::initial(4);
+        let first = mcts.search(&state);
+        assert_eq!(first.reused_visits(), 0);
+        assert_eq!(first.iterations(), 500);
+
+        state.perform(first.best_move().unwrap());
+        let reply = state.possible_moves()[0];
+        state.perform(reply);
+        let second = mcts.search(&state);
+        assert!(second.reused_visits() > 0);
+        assert!(state.validate_move(second.best_move().unwrap()).is_ok());
+
+        let unrelated = mcts.search(&State::initial(5));
+        assert_eq!(unrelated.reused_visits(), 0);
+    }
+
+    #[test]
+    fn test_without_current_team() {
+        let initial = State::initial(6);
+        let state = State::new(initial.board().clone(), initial.ambers().clone(), 0, None, None);
+        let result = Mcts::new(RandomRollout, 3).iterations(Some(100)).search(&state);
+        assert_eq!(result, MctsResult::default());
+    }
+}
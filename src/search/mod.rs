@@ -0,0 +1,19 @@
+mod score;
+mod alpha_beta;
+mod mcts;
+mod transposition;
+mod ordering;
+mod parallel;
+mod book;
+mod endgame;
+#[cfg(test)]
+mod fixtures;
+
+pub use score::*;
+pub use alpha_beta::*;
+pub use mcts::*;
+pub use transposition::*;
+pub use ordering::*;
+pub use parallel::*;
+pub use book::*;
+pub use endgame::*;
@@ -0,0 +1,127 @@
+use crate::game::{Board, Move, State, FIELD_COUNT};
+
+use super::MAX_PLY;
+
+const TABLE_MOVE_SCORE: i32 = 1 << 30;
+const AMBER_SCORE: i32 = 1 << 24;
+const CAPTURE_SCORE: i32 = 1 << 20;
+const KILLER_SCORE: i32 = 1 << 18;
+/// The maximum history score, below the killer scores.
+const MAX_HISTORY: i32 = KILLER_SCORE / 4;
+
+/// Ranks moves to search the most promising ones first: the move
+/// from the transposition table, moves earning ambers, captures
+/// (larger towers first), killer moves and finally quiet moves by
+/// their history. Ties keep the (deterministic) generation order.
+#[derive(Debug, Clone)]
+pub struct MoveOrdering {
+    /// Quiet moves that caused a cutoff, per ply.
+    killers: Vec<[Option<Move>; 2]>,
+    /// Cutoff statistics of quiet moves, indexed by origin and destination.
+    history: Vec<i32>,
+}
+
+impl MoveOrdering {
+    /// Creates empty move ordering tables.
+    pub fn new() -> Self {
+        Self {
+            killers: vec![[None; 2]; MAX_PLY],
+            history: vec![0; FIELD_COUNT * FIELD_COUNT],
+        }
+    }
+
+    /// Prepares the tables for a new search by clearing
+    /// the killers and aging the history.
+    pub fn new_search(&mut self) {
+        self.killers.fill([None; 2]);
+        for value in &mut self.history {
+            *value /= 2;
+        }
+    }
+
+    fn history_index(m: Move) -> usize {
+        Board::field_index(m.from()) * FIELD_COUNT + Board::field_index(m.to())
+    }
+
+    /// Scores a move for ordering, higher scores are searched first.
+    pub fn score(&self, state: &State, m: Move, table_move: Option<Move>, ply: usize) -> i32 {
+        let board = state.board();
+        if Some(m) == table_move {
+            return TABLE_MOVE_SCORE;
+        }
+        let ambers = board.amber_gain(m) as i32;
+        let captured = board.get(m.to()).map_or(0, |p| p.count() as i32);
+        if ambers > 0 || captured > 0 {
+            return ambers * AMBER_SCORE + captured * CAPTURE_SCORE;
+        }
+        match self.killers.get(ply) {
+            Some(&[Some(k), _]) if k == m => KILLER_SCORE + 1,
+            Some(&[_, Some(k)]) if k == m => KILLER_SCORE,
+            _ => self.history[Self::history_index(m)],
+        }
+    }
+
+    /// Sorts the moves from most to least promising.
+    pub fn order(&self, state: &State, moves: &mut [Move], table_move: Option<Move>, ply: usize) {
+        moves.sort_by_cached_key(|&m| -self.score(state, m, table_move, ply));
+    }
+
+    /// Records that the move caused a beta cutoff at the given depth and ply.
+    /// Only quiet moves are recorded, since tactical moves are ranked anyway.
+    pub fn record_cutoff(&mut self, state: &State, m: Move, depth: usize, ply: usize) {
+        let board = state.board();
+        if board.is_capture(m) || board.amber_gain(m) > 0 {
+            return;
+        }
+        if let Some(killers) = self.killers.get_mut(ply) {
+            if killers[0] != Some(m) {
+                killers[1] = killers[0];
+                killers[0] = Some(m);
+            }
+        }
+        let value = &mut self.history[Self::history_index(m)];
+        *value = (*value + (depth * depth) as i32).min(MAX_HISTORY);
+    }
+}
+
+impl Default for MoveOrdering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{game::{Move, State, Vec2}, search::MoveOrdering};
+
+    #[test]
+    fn test_order() {
+        let state = State::from_str("8/1R[2]6/3m4/2s5/8/8/M7/H5m[2]1 0:0 0 1 -").unwrap();
+        let mv = |fx, fy, tx, ty| Move::new(Vec2::new(fx, fy), Vec2::new(tx, ty));
+        let mut ordering = MoveOrdering::new();
+        let quiet = mv(0, 6, 0, 5);
+        ordering.record_cutoff(&state, quiet, 3, 1);
+
+        let mut moves = state.possible_moves();
+        ordering.order(&state, &mut moves, Some(mv(0, 7, 1, 6)), 1);
+        assert_eq!(moves[..4], [
+            mv(0, 7, 1, 6),
+            mv(1, 1, 3, 2),
+            mv(1, 1, 2, 3),
+            quiet,
+        ]);
+
+        let mut again = state.possible_moves();
+        ordering.order(&state, &mut again, Some(mv(0, 7, 1, 6)), 1);
+        assert_eq!(moves, again);
+
+        ordering.record_cutoff(&state, mv(1, 1, 3, 2), 3, 1);
+        ordering.new_search();
+        let mut fresh = state.possible_moves();
+        ordering.order(&state, &mut fresh, None, 1);
+        assert_eq!(fresh[..2], [mv(1, 1, 3, 2), mv(1, 1, 2, 3)]);
+        assert_eq!(ordering.score(&state, quiet, None, 1), 9 / 2);
+    }
+}
@@ -0,0 +1,140 @@
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc}, thread};
+
+use log::debug;
+
+use crate::{eval::Evaluator, game::State, util::TimeManager};
+
+use super::{AlphaBeta, SearchResult, TranspositionTable};
+
+/// A parallel alpha-beta search using Lazy SMP: helper threads
+/// search the same position independently and communicate only
+/// through the shared transposition table, which lets the main
+/// search reach greater depths in the same time.
+pub struct ParallelSearch<E> {
+    main: AlphaBeta<E>,
+    helpers: Vec<AlphaBeta<E>>,
+    /// Stops the helpers once the main search is done.
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl<E> ParallelSearch<E> where E: Evaluator {
+    /// Creates a new (single-threaded) search from the given search.
+    pub fn new(main: AlphaBeta<E>) -> Self {
+        Self { main: main.ages_table(false), helpers: Vec::new(), stop_flag: Arc::new(AtomicBool::new(false)) }
+    }
+
+    /// Sets the total number of threads, including the main thread.
+    pub fn threads(mut self, threads: usize) -> Self where E: Clone {
+        self.set_threads(threads);
+        self
+    }
+
+    /// Changes the total number of threads, including the main thread.
+    pub fn set_threads(&mut self, threads: usize) where E: Clone {
+        self.helpers = (1..threads.max(1))
+            .map(|i| self.main.helper(i, self.stop_flag.clone()))
+            .collect();
+    }
+
+    /// The total number of threads, including the main thread.
+    #[inline]
+    pub fn thread_count(&self) -> usize { 1 + self.helpers.len() }
+
+    /// The search run on the main thread.
+    #[inline]
+    pub fn main(&self) -> &AlphaBeta<E> { &self.main }
+
+    /// The transposition table shared by all threads.
+    #[inline]
+    pub fn table(&self) -> &Arc<TranspositionTable> { self.main.table() }
+}
+
+impl<E> ParallelSearch<E> where E: Evaluator + Send {
+    /// Searches the best move in the given state on all threads.
+    pub fn search(&mut self, state: &State) -> SearchResult {
+        self.table().new_search();
+        self.run(state, |main| main.search(state))
+    }
+
+    /// Searches the best move in the given state on all threads,
+    /// stopping at the time manager's deadline.
+    pub fn search_with(&mut self, state: &State, time: &TimeManager) -> SearchResult {
+        self.table().new_search();
+        self.run(state, |main| main.search_with(state, time))
+    }
+
+    /// Searches like `search_with`, but within the table's current
+    /// generation, e.g. after pondering on the same position, whose
+    /// entries would otherwise be replaced as if they were stale.
+    pub fn continue_with(&mut self, state: &State, time: &TimeManager) -> SearchResult {
+        self.run(state, |main| main.search_with(state, time))
+    }
+
+    /// Runs the search on all threads, which search within
+    /// the same generation of the table.
+    fn run(&mut self, state: &State, search_main: impl FnOnce(&mut AlphaBeta<E>) -> SearchResult) -> SearchResult {
+        if self.helpers.is_empty() {
+            return search_main(&mut self.main);
+        }
+
+        self.stop_flag.store(false, Ordering::Relaxed);
+        let (main, helpers, stop_flag) = (&mut self.main, &mut self.helpers, &self.stop_flag);
+        let (main_result, helper_results) = thread::scope(|scope| {
+            let handles = helpers.iter_mut()
+                .map(|helper| scope.spawn(move || helper.search(state)))
+                .collect::<Vec<_>>();
+            let main_result = search_main(main);
+            stop_flag.store(true, Ordering::Relaxed);
+            let helper_results = handles.into_iter()
+                .filter_map(|handle| handle.join().ok())
+                .collect::<Vec<_>>();
+            (main_result, helper_results)
+        });
+
+        // Prefer a helper's result only if it completed a deeper iteration
+        let nodes = main_result.nodes() + helper_results.iter().map(|r| r.nodes()).sum::<u64>();
+        let mut result = helper_results.into_iter()
+            .filter(|r| r.depth() > main_result.depth() && r.best_move().is_some())
+            .max_by_key(|r| r.depth())
+            .unwrap_or(main_result);
+        result.nodes = nodes;
+        debug!("Parallel search on {} threads: depth {}, {} nodes", self.thread_count(), result.depth(), nodes);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, time::Duration};
+
+    use crate::{eval::SimpleEvaluator, game::State, search::{fixtures, AlphaBeta, ParallelSearch}, util::TimeManager};
+
+    #[test]
+    fn test_matches_single_thread() {
+        // The helpers only speed up the search through the table,
+        // so a fixed-depth search finds the same score
+        let (winning, _) = fixtures::winning_amber();
+        let positions = [winning, State::from_str("R6r/H6s/M6m/H6s/S6h/M6m/S6h/R6r 0:0 0 1 -").unwrap()];
+        for state in positions {
+            let single = ParallelSearch::new(AlphaBeta::new(SimpleEvaluator).max_depth(4)).search(&state);
+            let multi = ParallelSearch::new(AlphaBeta::new(SimpleEvaluator).max_depth(4)).threads(4).search(&state);
+            assert_eq!(multi.score(), single.score());
+            assert_eq!(multi.depth(), single.depth());
+        }
+    }
+
+    #[test]
+    fn test_threads() {
+        let state = State::from_str("5m2/HH5s/S6R[2]/8/8/MS[2]4s1/2R1r2h/M6h 1:0 20 1 f5e7").unwrap();
+        let mut search = ParallelSearch::new(AlphaBeta::new(SimpleEvaluator).max_depth(4)).threads(4);
+        assert_eq!(search.thread_count(), 4);
+        let generation = search.table().generation();
+        let result = search.search(&state);
+        assert_eq!(search.table().generation(), generation.wrapping_add(1));
+        let resumed = search.continue_with(&state, &TimeManager::with_limit(Duration::from_secs(10)));
+        assert_eq!(search.table().generation(), generation.wrapping_add(1));
+        assert!(resumed.nodes() < result.nodes());
+        assert_eq!(result.depth(), 4);
+        assert!(state.validate_move(result.best_move().unwrap()).is_ok());
+    }
+}
@@ -0,0 +1,40 @@
+use crate::game::{State, Team};
+
+/// The score of a won game. Wins in fewer plies score higher.
+pub const WIN_SCORE: i32 = 1_000_000;
+/// A score larger than any possible score.
+pub const INFINITY: i32 = WIN_SCORE + 1;
+/// The maximum search depth in plies.
+pub const MAX_PLY: usize = 128;
+
+/// Checks whether the score denotes a decided game.
+#[inline]
+pub fn is_win_score(score: i32) -> bool {
+    score.abs() > WIN_SCORE - MAX_PLY as i32
+}
+
+/// Scores a finished game from the given team's perspective,
+/// preferring quicker wins and slower losses. Returns `None`
+/// if the game is not over.
+pub fn terminal_score(state: &State, team: Team, ply: usize) -> Option<i32> {
+    let outcome = state.outcome()?;
+    Some(match outcome.winner() {
+        Some(winner) if winner == team => WIN_SCORE - ply as i32,
+        Some(_) => -WIN_SCORE + ply as i32,
+        None => 0,
+    })
+}
+
+/// Converts a score relative to the root into one relative to the
+/// node at the given ply, as stored in the transposition table.
+#[inline]
+pub fn score_to_table(score: i32, ply: usize) -> i32 {
+    if is_win_score(score) { score + score.signum() * ply as i32 } else { score }
+}
+
+/// Converts a score from the transposition table into one
+/// relative to the root.
+#[inline]
+pub fn score_from_table(score: i32, ply: usize) -> i32 {
+    if is_win_score(score) { score - score.signum() * ply as i32 } else { score }
+}
@@ -0,0 +1,265 @@
+use std::{mem::size_of, sync::atomic::{AtomicU64, AtomicU8, Ordering}};
+
+use crate::game::{Board, Move};
+
+/// The default size of a transposition table in megabytes.
+pub const DEFAULT_TABLE_SIZE_MB: usize = 16;
+
+/// The kind of bound a stored score represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bound {
+    /// The score is exact.
+    Exact,
+    /// The score is a lower bound (the search failed high).
+    Lower,
+    /// The score is an upper bound (the search failed low).
+    Upper,
+}
+
+/// A search result stored in the transposition table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TableEntry {
+    depth: u8,
+    bound: Bound,
+    score: i32,
+    best_move: Option<Move>,
+}
+
+impl TableEntry {
+    /// Creates a new entry. Moves must be on the board.
+    pub fn new(depth: usize, bound: Bound, score: i32, best_move: Option<Move>) -> Self {
+        Self { depth: depth.min(u8::MAX as usize) as u8, bound, score, best_move }
+    }
+
+    /// The depth the position was searched to.
+    #[inline]
+    pub fn depth(self) -> usize { self.depth as usize }
+
+    /// The kind of bound the score represents.
+    #[inline]
+    pub fn bound(self) -> Bound { self.bound }
+
+    /// The score of the position.
+    #[inline]
+    pub fn score(self) -> i32 { self.score }
+
+    /// The best move found in the position, if any.
+    #[inline]
+    pub fn best_move(self) -> Option<Move> { self.best_move }
+
+    /// Packs the entry along with the generation into 64 bits:
+    /// the score (32 bits), the depth (8 bits), the bound (2 bits),
+    /// the move (1 + 2 * 6 bits), the generation (8 bits) and
+    /// a flag marking the slot as occupied.
+    fn pack(self, generation: u8) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 0,
+            Bound::Lower => 1,
+            Bound::Upper => 2,
+        };
+        let packed_move = self.best_move.map_or(0, |m| 1 | (Board::field_index(m.from()) as u64) << 1 | (Board::field_index(m.to()) as u64) << 7);
+        (self.score as u32 as u64)
+            | (self.depth as u64) << 32
+            | bound << 40
+            | packed_move << 42
+            | (generation as u64) << 55
+            | 1 << 63
+    }
+
+    /// Unpacks an entry and its generation.
+    fn unpack(data: u64) -> Option<(Self, u8)> {
+        if data >> 63 == 0 {
+            return None;
+        }
+        let bound = match (data >> 40) & 0b11 {
+            0 => Bound::Exact,
+            1 => Bound::Lower,
+            _ => Bound::Upper,
+        };
+        let packed_move = (data >> 42) & 0x1FFF;
+        let best_move = if packed_move & 1 == 1 {
+            Some(Move::new(Board::field_pos(((packed_move >> 1) & 0x3F) as usize), Board::field_pos((packed_move >> 7) as usize)))
+        } else {
+            None
+        };
+        let entry = Self { depth: (data >> 32) as u8, bound, score: data as u32 as i32, best_move };
+        Some((entry, (data >> 55) as u8))
+    }
+}
+
+/// A slot storing the key xor'ed with the data, so torn writes
+/// from concurrent access are detected when probing.
+#[derive(Debug, Default)]
+struct Slot {
+    check: AtomicU64,
+    data: AtomicU64,
+}
+
+impl Slot {
+    fn load(&self, key: u64) -> Option<(TableEntry, u8)> {
+        let data = self.data.load(Ordering::Relaxed);
+        if self.check.load(Ordering::Relaxed) ^ data == key {
+            TableEntry::unpack(data)
+        } else {
+            None
+        }
+    }
+
+    fn load_any(&self) -> Option<(TableEntry, u8)> {
+        TableEntry::unpack(self.data.load(Ordering::Relaxed))
+    }
+
+    fn store(&self, key: u64, data: u64) {
+        self.check.store(key ^ data, Ordering::Relaxed);
+        self.data.store(data, Ordering::Relaxed);
+    }
+
+    fn clear(&self) {
+        self.check.store(0, Ordering::Relaxed);
+        self.data.store(0, Ordering::Relaxed);
+    }
+}
+
+/// A bucket of two slots, one replaced only by deeper (or newer)
+/// entries and one that is always replaced.
+#[derive(Debug, Default)]
+struct Bucket {
+    depth_preferred: Slot,
+    always_replace: Slot,
+}
+
+/// A fixed-size hash table mapping Zobrist keys of positions to search
+/// results. It can be shared between threads: Concurrent writes never
+/// block, inconsistent entries are discarded when probing.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    buckets: Box<[Bucket]>,
+    generation: AtomicU8,
+}
+
+impl TranspositionTable {
+    /// Creates a table using at most the given number of megabytes.
+    pub fn new(size_mb: usize) -> Self {
+        let max_buckets = (size_mb * 1024 * 1024 / size_of::<Bucket>()).max(1);
+        let bucket_count = if max_buckets.is_power_of_two() { max_buckets } else { max_buckets.next_power_of_two() / 2 };
+        Self {
+            buckets: (0..bucket_count).map(|_| Bucket::default()).collect(),
+            generation: AtomicU8::new(0),
+        }
+    }
+
+    /// The number of entries the table can hold.
+    pub fn capacity(&self) -> usize {
+        2 * self.buckets.len()
+    }
+
+    /// The memory used by the entries in bytes.
+    pub fn size_bytes(&self) -> usize {
+        self.buckets.len() * size_of::<Bucket>()
+    }
+
+    fn bucket(&self, key: u64) -> &Bucket {
+        &self.buckets[key as usize & (self.buckets.len() - 1)]
+    }
+
+    /// Marks the beginning of a new search, which lets
+    /// entries from previous searches be replaced.
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The current generation, i.e. the number of searches (wrapping).
+    #[inline]
+    pub fn generation(&self) -> u8 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Looks up the entry for the given key.
+    pub fn probe(&self, key: u64) -> Option<TableEntry> {
+        let bucket = self.bucket(key);
+        bucket.depth_preferred.load(key)
+            .or_else(|| bucket.always_replace.load(key))
+            .map(|(entry, _)| entry)
+    }
+
+    /// Stores an entry for the given key. It replaces the depth-preferred
+    /// slot if that holds the same position, a shallower search or an
+    /// entry from a previous search, otherwise the always-replace slot.
+    pub fn store(&self, key: u64, entry: TableEntry) {
+        let generation = self.generation.load(Ordering::Relaxed);
+        let bucket = self.bucket(key);
+        let replace_preferred = bucket.depth_preferred.load(key).is_some() || match bucket.depth_preferred.load_any() {
+            Some((existing, existing_generation)) => existing_generation != generation || entry.depth >= existing.depth,
+            None => true,
+        };
+        let slot = if replace_preferred { &bucket.depth_preferred } else { &bucket.always_replace };
+        slot.store(key, entry.pack(generation));
+    }
+
+    /// Removes all entries.
+    pub fn clear(&self) {
+        for bucket in self.buckets.iter() {
+            bucket.depth_preferred.clear();
+            bucket.always_replace.clear();
+        }
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_TABLE_SIZE_MB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{game::{Move, Vec2}, search::{Bound, TableEntry, TranspositionTable}};
+
+    #[test]
+    fn test_packing() {
+        let m = Move::new(Vec2::new(7, 0), Vec2::new(5, 1));
+        for entry in [
+            TableEntry::new(3, Bound::Exact, -1234, Some(m)),
+            TableEntry::new(255, Bound::Upper, i32::MAX, None),
+            TableEntry::new(0, Bound::Lower, i32::MIN, Some(m)),
+        ] {
+            assert_eq!(TableEntry::unpack(entry.pack(17)), Some((entry, 17)));
+        }
+        assert_eq!(TableEntry::unpack(0), None);
+    }
+
+    #[test]
+    fn test_size() {
+        let table = TranspositionTable::new(1);
+        assert_eq!(table.size_bytes(), 1024 * 1024);
+        assert_eq!(table.capacity(), 2 * 1024 * 1024 / 32);
+        assert!(TranspositionTable::new(3).size_bytes() <= 3 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_replacement() {
+        let table = TranspositionTable::new(1);
+        let buckets = table.capacity() as u64 / 2;
+        let (a, b, c) = (5, 5 + buckets, 5 + 2 * buckets);
+        let deep = TableEntry::new(8, Bound::Exact, 10, None);
+        let shallow = TableEntry::new(2, Bound::Lower, 20, None);
+
+        table.store(a, deep);
+        table.store(b, shallow);
+        assert_eq!(table.probe(a), Some(deep));
+        assert_eq!(table.probe(b), Some(shallow));
+
+        table.store(c, shallow);
+        assert_eq!(table.probe(a), Some(deep));
+        assert_eq!(table.probe(b), None);
+        assert_eq!(table.probe(c), Some(shallow));
+
+        table.new_search();
+        table.store(b, shallow);
+        assert_eq!(table.probe(a), None);
+        assert_eq!(table.probe(b), Some(shallow));
+
+        table.clear();
+        assert_eq!(table.probe(b), None);
+    }
+}
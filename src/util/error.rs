@@ -3,6 +3,8 @@ use std::str::{ParseBoolError, Utf8Error};
 use std::num::{ParseIntError, ParseFloatError};
 use quick_xml::Error as XmlError;
 
+use crate::game::InvalidMove;
+
 use super::Element;
 
 /// A custom error type that abstracts over
@@ -20,6 +22,8 @@ pub enum SCError {
     UnknownElement(Element),
     UnknownVariant(String),
     InvalidState(String),
+    InvalidMove(InvalidMove),
+    InvalidNotation(String),
     ServerError(String),
     Eof,
     Custom(String)
@@ -49,6 +53,10 @@ impl From<XmlError> for SCError {
     fn from(error: XmlError) -> Self { Self::Xml(error) }
 }
 
+impl From<InvalidMove> for SCError {
+    fn from(error: InvalidMove) -> Self { Self::InvalidMove(error) }
+}
+
 impl From<String> for SCError {
     fn from(error: String) -> Self { Self::Custom(error) }
 }
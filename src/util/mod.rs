@@ -1,9 +1,11 @@
 mod error;
 mod result;
+// Exported at the crate root by `#[macro_export]`
 mod macros;
 mod xml;
+mod time;
 
 pub use error::*;
 pub use result::*;
-pub use macros::*;
 pub use xml::*;
+pub use time::*;
@@ -0,0 +1,130 @@
+use std::time::{Duration, Instant};
+
+/// The time the server grants per move before
+/// the player loses with a soft timeout.
+pub const SOFT_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// A configuration that determines how much
+/// time the client spends on a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeConfig {
+    /// The total time available per move, measured
+    /// from the arrival of the move request.
+    pub budget: Duration,
+    /// The time reserved for sending the move and
+    /// for network latency.
+    pub safety_margin: Duration,
+}
+
+impl TimeConfig {
+    /// The time left for the delegate after subtracting the safety margin.
+    pub fn usable(&self) -> Duration {
+        self.budget.saturating_sub(self.safety_margin)
+    }
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        Self {
+            budget: SOFT_TIMEOUT,
+            safety_margin: Duration::from_millis(300),
+        }
+    }
+}
+
+/// Tracks the deadline of a single move, which
+/// searches can poll to stop in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeManager {
+    start: Instant,
+    deadline: Instant,
+}
+
+impl TimeManager {
+    /// Creates a time manager for a move requested at the given instant.
+    pub fn new(start: Instant, config: &TimeConfig) -> Self {
+        Self { start, deadline: start + config.usable() }
+    }
+
+    /// Creates a time manager that expires after the given time from now.
+    pub fn with_limit(limit: Duration) -> Self {
+        let start = Instant::now();
+        Self { start, deadline: start + limit }
+    }
+
+    /// Restricts the deadline to at most the given time after the start.
+    pub fn limited(mut self, limit: Option<Duration>) -> Self {
+        if let Some(limit) = limit {
+            self.deadline = self.deadline.min(self.start + limit);
+        }
+        self
+    }
+
+    /// The instant at which the move was requested.
+    #[inline]
+    pub fn start(&self) -> Instant { self.start }
+
+    /// The instant at which the move has to be chosen.
+    #[inline]
+    pub fn deadline(&self) -> Instant { self.deadline }
+
+    /// The total time available for the move.
+    pub fn allotted(&self) -> Duration {
+        self.deadline.saturating_duration_since(self.start)
+    }
+
+    /// The time spent since the move was requested.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// The time left until the deadline.
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    /// Whether another iteration of an iterative deepening search
+    /// should be started. Since every iteration usually takes longer
+    /// than all previous ones together, this is only the case if less
+    /// than half of the available time is used up.
+    pub fn can_start_iteration(&self) -> bool {
+        self.elapsed() * 2 < self.allotted()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{TimeConfig, TimeManager};
+
+    #[test]
+    fn test_deadline() {
+        let config = TimeConfig { budget: Duration::from_millis(2000), safety_margin: Duration::from_millis(300) };
+        let start = Instant::now();
+        let time = TimeManager::new(start, &config);
+        assert_eq!(time.deadline(), start + Duration::from_millis(1700));
+        assert_eq!(time.allotted(), Duration::from_millis(1700));
+        assert!(!time.is_expired());
+        assert!(time.can_start_iteration());
+
+        let limited = time.limited(Some(Duration::from_millis(500)));
+        assert_eq!(limited.allotted(), Duration::from_millis(500));
+        assert_eq!(time.limited(Some(Duration::from_secs(5))), time);
+    }
+
+    #[test]
+    fn test_expired() {
+        let config = TimeConfig { budget: Duration::from_millis(100), safety_margin: Duration::from_millis(200) };
+        let time = TimeManager::new(Instant::now(), &config);
+        assert_eq!(time.allotted(), Duration::ZERO);
+        assert!(time.is_expired());
+        assert!(!time.can_start_iteration());
+        assert_eq!(time.remaining(), Duration::ZERO);
+    }
+}
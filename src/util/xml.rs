@@ -31,7 +31,8 @@ pub struct ElementBuilder<'a> {
 
 impl Element {
     /// Creates a new XML element builder.
-    pub fn new(name: &str) -> ElementBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(name: &str) -> ElementBuilder<'_> {
         ElementBuilder::new(name)
     }
 
@@ -175,7 +176,7 @@ impl<'a> ElementBuilder<'a> {
     /// Creates a new XML node builder with the
     /// specified tag name.
     pub fn new(name: &'a str) -> Self {
-        Self { name: name, content: "", attributes: HashMap::new(), childs: Vec::new() }
+        Self { name, content: "", attributes: HashMap::new(), childs: Vec::new() }
     }
     
     /// Sets the tag name of the XML node.
@@ -249,7 +250,6 @@ impl<'a> TryFrom<&BytesStart<'a>> for Element {
             name: str::from_utf8(start.name())?.to_owned(),
             content: String::new(),
             attributes: start.attributes()
-                .into_iter()
                 .map(|res| {
                     let attribute = res?;
                     let key = str::from_utf8(attribute.key)?.to_owned();